@@ -65,8 +65,8 @@ fn criterion_benchmark(c: &mut Criterion) {
     let elements = 10 * 1024;
     let symbol_size = 512;
     let mut data: Vec<u8> = vec![0; elements];
-    for i in 0..elements {
-        data[i] = rand::rng().random();
+    for element in &mut data {
+        *element = rand::rng().random();
     }
 
     let encode_data = data.clone();
@@ -76,7 +76,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         b.iter(|| {
             let config = ObjectTransmissionInformation::new(0, symbol_size, 0, 1, 1);
             let encoder = SourceBlockEncoder::new(1, &config, &encode_data);
-            return encoder.source_packets();
+            encoder.source_packets()
         })
     });
     group.finish();
@@ -89,7 +89,7 @@ fn criterion_benchmark(c: &mut Criterion) {
             let config = ObjectTransmissionInformation::new(0, symbol_size, 0, 1, 1);
             let encoder = SourceBlockEncoder::new(1, &config, &roundtrip_data);
             let mut decoder = SourceBlockDecoder::new(1, &config, elements as u64);
-            return decoder.decode(encoder.source_packets());
+            decoder.decode(encoder.source_packets())
         })
     });
     group.finish();
@@ -103,7 +103,7 @@ fn criterion_benchmark(c: &mut Criterion) {
             let encoder = SourceBlockEncoder::new(1, &config, &repair_data);
             let repair_packets = (elements / symbol_size as usize) as u32;
             let mut decoder = SourceBlockDecoder::new(1, &config, elements as u64);
-            return decoder.decode(encoder.repair_packets(0, repair_packets));
+            decoder.decode(encoder.repair_packets(0, repair_packets))
         })
     });
     group.finish();
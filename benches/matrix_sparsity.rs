@@ -11,18 +11,16 @@ fn main() {
         let (a, hdpc) = generate_constraint_matrix::<SparseBinaryMatrix>(num_symbols, &indices);
         let mut density = 0;
         let mut row_density = vec![0; a.height()];
-        for i in 0..a.height() {
+        for (i, row) in row_density.iter_mut().enumerate() {
             for j in 0..a.width() {
-                if i < a.height() - hdpc.height() {
-                    if a.get(i, j) != Octet::zero() {
-                        density += 1;
-                        row_density[i] += 1;
-                    }
+                let value = if i < a.height() - hdpc.height() {
+                    a.get(i, j)
                 } else {
-                    if hdpc.get(i - (a.height() - hdpc.height()), j) != Octet::zero() {
-                        density += 1;
-                        row_density[i] += 1;
-                    }
+                    hdpc.get(i - (a.height() - hdpc.height()), j)
+                };
+                if value != Octet::zero() {
+                    density += 1;
+                    *row += 1;
                 }
             }
         }
@@ -0,0 +1,909 @@
+use core::fmt;
+use std::collections::HashMap;
+use std::vec::Vec;
+
+use crate::base::{
+    EncodingPacket, ObjectTransmissionInformation, ObjectTransmissionInformationError,
+};
+use crate::decoder::DecodeError;
+use crate::decoder::Decoder;
+use crate::encoder::Encoder;
+
+/// Header Extension Type of EXT_NOP, as defined in RFC 5651 section 5.2.1.
+pub const EXT_NOP: u8 = 0;
+/// Header Extension Type of EXT_FTI, as defined in RFC 5775 section 5.1.
+pub const EXT_FTI: u8 = 64;
+
+const LCT_VERSION: u8 = 1;
+// Fixed header fields and the 32 bit congestion control information
+const MIN_HEADER_BYTES: usize = 8;
+// HET and HEL, followed by the Object Transmission Information and two bytes of padding
+const EXT_FTI_BYTES: usize = 16;
+
+/// Reason a packet was rejected by `AlcPacket::deserialize`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AlcError {
+    /// The packet is shorter than its header claims.
+    Truncated,
+    /// The LCT version is not 1.
+    UnsupportedVersion(u8),
+    /// The congestion control information is longer than 32 bits.
+    UnsupportedCongestionControlLength(usize),
+    /// The TSI or TOI does not fit in 64 bits.
+    IdentifierTooLong,
+    /// A header extension has a zero length, or extends past the end of the header.
+    InvalidHeaderExtension(u8),
+    /// The EXT_FTI header extension contains an invalid object transmission information.
+    InvalidTransmissionInformation(ObjectTransmissionInformationError),
+    /// The encoding symbol is shorter than the FEC Payload ID.
+    TruncatedPayload,
+    /// The decoder of the packet's object rejected it.
+    Decode(DecodeError),
+}
+
+impl fmt::Display for AlcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlcError::Truncated => write!(f, "packet is shorter than its LCT header"),
+            AlcError::UnsupportedVersion(version) => {
+                write!(f, "unsupported LCT version {version}")
+            }
+            AlcError::UnsupportedCongestionControlLength(length) => {
+                write!(
+                    f,
+                    "unsupported congestion control information of {length} bytes"
+                )
+            }
+            AlcError::IdentifierTooLong => write!(f, "TSI or TOI exceeds 64 bits"),
+            AlcError::InvalidHeaderExtension(het) => {
+                write!(f, "invalid header extension of type {het}")
+            }
+            AlcError::InvalidTransmissionInformation(err) => {
+                write!(f, "invalid object transmission information: {err}")
+            }
+            AlcError::TruncatedPayload => write!(f, "payload is shorter than the FEC Payload ID"),
+            AlcError::Decode(err) => write!(f, "packet rejected by decoder: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for AlcError {}
+
+/// LCT header extension, other than EXT_FTI. See RFC 5651 section 5.2.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeaderExtension {
+    het: u8,
+    content: Vec<u8>,
+}
+
+impl HeaderExtension {
+    /// Creates an extension of type `het`. Types 128 and above have a fixed length, and `content`
+    /// must be exactly 3 bytes. Otherwise, `content` is padded with zeros to a multiple of 32
+    /// bits, including the HET and HEL fields.
+    pub fn new(het: u8, content: Vec<u8>) -> HeaderExtension {
+        if het >= 128 {
+            assert_eq!(content.len(), 3);
+        } else {
+            assert!((2 + content.len()).div_ceil(4) <= u8::MAX as usize);
+        }
+        HeaderExtension { het, content }
+    }
+
+    /// Header Extension Type (HET).
+    pub fn het(&self) -> u8 {
+        self.het
+    }
+
+    /// Content following the HET, and HEL if present.
+    pub fn content(&self) -> &[u8] {
+        &self.content
+    }
+
+    fn serialize_into(&self, serialized: &mut Vec<u8>) {
+        serialized.push(self.het);
+        if self.het >= 128 {
+            serialized.extend_from_slice(&self.content);
+        } else {
+            let words = (2 + self.content.len()).div_ceil(4);
+            serialized.push(words as u8);
+            serialized.extend_from_slice(&self.content);
+            serialized.resize(serialized.len() + words * 4 - 2 - self.content.len(), 0);
+        }
+    }
+
+    fn serialized_len(&self) -> usize {
+        if self.het >= 128 {
+            4
+        } else {
+            (2 + self.content.len()).div_ceil(4) * 4
+        }
+    }
+}
+
+/// Layered Coding Transport header, as defined in RFC 5651 section 5.1, restricted to a 32 bit
+/// congestion control information field and TSI and TOI of at most 64 bits.
+///
+/// The TSI and TOI are serialized with the shortest lengths the header format allows, of at
+/// least 16 bits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LctHeader {
+    tsi: u64,
+    toi: u64,
+    codepoint: u8,
+    congestion_control: u32,
+    protocol_specific: u8,
+    close_session: bool,
+    close_object: bool,
+    oti: Option<ObjectTransmissionInformation>,
+    extensions: Vec<HeaderExtension>,
+}
+
+impl LctHeader {
+    /// Creates a header for object `toi` of session `tsi`. The TSI must fit in 48 bits.
+    pub fn new(tsi: u64, toi: u64) -> LctHeader {
+        assert!(tsi < 1 << 48, "TSI must fit in 48 bits");
+        LctHeader {
+            tsi,
+            toi,
+            codepoint: 0,
+            congestion_control: 0,
+            protocol_specific: 0,
+            close_session: false,
+            close_object: false,
+            oti: None,
+            extensions: vec![],
+        }
+    }
+
+    /// Transport Session Identifier.
+    pub fn tsi(&self) -> u64 {
+        self.tsi
+    }
+
+    /// Transport Object Identifier.
+    pub fn toi(&self) -> u64 {
+        self.toi
+    }
+
+    pub fn codepoint(&self) -> u8 {
+        self.codepoint
+    }
+
+    pub fn set_codepoint(&mut self, codepoint: u8) {
+        self.codepoint = codepoint;
+    }
+
+    /// Congestion Control Information (CCI).
+    pub fn congestion_control(&self) -> u32 {
+        self.congestion_control
+    }
+
+    pub fn set_congestion_control(&mut self, congestion_control: u32) {
+        self.congestion_control = congestion_control;
+    }
+
+    /// Protocol-Specific Indication (PSI) bits.
+    pub fn protocol_specific(&self) -> u8 {
+        self.protocol_specific
+    }
+
+    pub fn set_protocol_specific(&mut self, protocol_specific: u8) {
+        assert!(protocol_specific < 4);
+        self.protocol_specific = protocol_specific;
+    }
+
+    /// Whether the Close Session flag (A) is set, indicating that the sender will soon stop
+    /// sending packets for the session.
+    pub fn close_session(&self) -> bool {
+        self.close_session
+    }
+
+    pub fn set_close_session(&mut self, close_session: bool) {
+        self.close_session = close_session;
+    }
+
+    /// Whether the Close Object flag (B) is set, indicating that the sender will soon stop
+    /// sending packets for the object.
+    pub fn close_object(&self) -> bool {
+        self.close_object
+    }
+
+    pub fn set_close_object(&mut self, close_object: bool) {
+        self.close_object = close_object;
+    }
+
+    /// Object transmission information carried in the EXT_FTI header extension.
+    pub fn oti(&self) -> Option<ObjectTransmissionInformation> {
+        self.oti
+    }
+
+    pub fn set_oti(&mut self, oti: Option<ObjectTransmissionInformation>) {
+        self.oti = oti;
+    }
+
+    /// Header extensions other than EXT_FTI, in the order they appear in the header.
+    pub fn extensions(&self) -> &[HeaderExtension] {
+        &self.extensions
+    }
+
+    pub fn add_extension(&mut self, extension: HeaderExtension) {
+        assert_ne!(extension.het(), EXT_FTI, "use set_oti() for EXT_FTI");
+        self.extensions.push(extension);
+    }
+
+    // Returns the S, O, and H fields
+    fn identifier_lengths(&self) -> (u8, u8, u8) {
+        let bits = |value: u64| (64 - value.leading_zeros()).max(1);
+        let (tsi_bits, toi_bits) = (bits(self.tsi), bits(self.toi));
+        // H applies to both the TSI and TOI, so choose it to minimize their total length
+        (0..=1)
+            .filter_map(|h| {
+                let s = (0..=1).find(|s| 32 * s + 16 * h >= tsi_bits && 32 * s + 16 * h > 0)?;
+                let o = (0..=3).find(|o| 32 * o + 16 * h >= toi_bits && 32 * o + 16 * h > 0)?;
+                Some((s as u8, o as u8, h as u8))
+            })
+            .min_by_key(|(s, o, h)| s + o + h)
+            .unwrap()
+    }
+
+    fn serialized_len(&self) -> usize {
+        let (s, o, h) = self.identifier_lengths();
+        let mut len = MIN_HEADER_BYTES + 4 * (s + o + h) as usize;
+        if self.oti.is_some() {
+            len += EXT_FTI_BYTES;
+        }
+        len + self
+            .extensions
+            .iter()
+            .map(|extension| extension.serialized_len())
+            .sum::<usize>()
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut serialized = Vec::with_capacity(self.serialized_len());
+        self.serialize_into(&mut serialized);
+        serialized
+    }
+
+    fn serialize_into(&self, serialized: &mut Vec<u8>) {
+        let (s, o, h) = self.identifier_lengths();
+        let header_words = self.serialized_len() / 4;
+        assert!(
+            header_words <= u8::MAX as usize,
+            "header extensions are too long"
+        );
+        serialized.push((LCT_VERSION << 4) | self.protocol_specific);
+        serialized.push(
+            (s << 7)
+                | (o << 5)
+                | (h << 4)
+                | ((self.close_session as u8) << 1)
+                | self.close_object as u8,
+        );
+        serialized.push(header_words as u8);
+        serialized.push(self.codepoint);
+        serialized.extend_from_slice(&self.congestion_control.to_be_bytes());
+        let tsi_bytes = (4 * s + 2 * h) as usize;
+        serialized.extend_from_slice(&self.tsi.to_be_bytes()[8 - tsi_bytes..]);
+        // The TOI may be wider than 64 bits, when the TSI requires the half-word flag
+        let toi_bytes = (4 * o + 2 * h) as usize;
+        serialized.resize(serialized.len() + toi_bytes.saturating_sub(8), 0);
+        serialized.extend_from_slice(&self.toi.to_be_bytes()[8 - toi_bytes.min(8)..]);
+
+        if let Some(oti) = self.oti {
+            serialized.push(EXT_FTI);
+            serialized.push((EXT_FTI_BYTES / 4) as u8);
+            serialized.extend_from_slice(&oti.serialize());
+            serialized.extend_from_slice(&[0, 0]);
+        }
+        for extension in self.extensions.iter() {
+            extension.serialize_into(serialized);
+        }
+    }
+
+    /// Parses a header from the start of `data`, and returns it along with its length in bytes.
+    pub fn deserialize(data: &[u8]) -> Result<(LctHeader, usize), AlcError> {
+        if data.len() < MIN_HEADER_BYTES {
+            return Err(AlcError::Truncated);
+        }
+        let version = data[0] >> 4;
+        if version != LCT_VERSION {
+            return Err(AlcError::UnsupportedVersion(version));
+        }
+        let cci_bytes = 4 * (((data[0] >> 2) & 0x3) as usize + 1);
+        if cci_bytes != 4 {
+            return Err(AlcError::UnsupportedCongestionControlLength(cci_bytes));
+        }
+        let s = (data[1] >> 7) as usize;
+        let o = ((data[1] >> 5) & 0x3) as usize;
+        let h = ((data[1] >> 4) & 0x1) as usize;
+        let header_len = 4 * data[2] as usize;
+        let tsi_bytes = 4 * s + 2 * h;
+        let toi_bytes = 4 * o + 2 * h;
+        let extensions_start = MIN_HEADER_BYTES + tsi_bytes + toi_bytes;
+        if header_len < extensions_start || data.len() < header_len {
+            return Err(AlcError::Truncated);
+        }
+
+        let read_identifier = |bytes: &[u8]| -> Result<u64, AlcError> {
+            let (high, low) = bytes.split_at(bytes.len().saturating_sub(8));
+            if high.iter().any(|byte| *byte != 0) {
+                return Err(AlcError::IdentifierTooLong);
+            }
+            Ok(low.iter().fold(0, |acc, byte| (acc << 8) | *byte as u64))
+        };
+        let tsi = read_identifier(&data[MIN_HEADER_BYTES..MIN_HEADER_BYTES + tsi_bytes])?;
+        let toi = read_identifier(&data[MIN_HEADER_BYTES + tsi_bytes..extensions_start])?;
+
+        let mut header = LctHeader {
+            tsi,
+            toi,
+            codepoint: data[3],
+            congestion_control: u32::from_be_bytes(data[4..8].try_into().unwrap()),
+            protocol_specific: data[0] & 0x3,
+            close_session: data[1] & 0x2 != 0,
+            close_object: data[1] & 0x1 != 0,
+            oti: None,
+            extensions: vec![],
+        };
+
+        let mut remaining = &data[extensions_start..header_len];
+        while let Some(&het) = remaining.first() {
+            let length = if het >= 128 {
+                4
+            } else {
+                4 * *remaining.get(1).unwrap_or(&0) as usize
+            };
+            if length == 0 || length > remaining.len() {
+                return Err(AlcError::InvalidHeaderExtension(het));
+            }
+            let (extension, rest) = remaining.split_at(length);
+            remaining = rest;
+            if het == EXT_FTI {
+                let oti: &[u8; 12] = extension
+                    .get(2..14)
+                    .and_then(|oti| oti.try_into().ok())
+                    .ok_or(AlcError::InvalidHeaderExtension(het))?;
+                header.oti = Some(
+                    ObjectTransmissionInformation::try_deserialize(oti)
+                        .map_err(AlcError::InvalidTransmissionInformation)?,
+                );
+            } else if het >= 128 {
+                header.extensions.push(HeaderExtension {
+                    het,
+                    content: extension[1..].to_vec(),
+                });
+            } else {
+                header.extensions.push(HeaderExtension {
+                    het,
+                    content: extension[2..].to_vec(),
+                });
+            }
+        }
+
+        Ok((header, header_len))
+    }
+}
+
+/// ALC packet, as defined in RFC 5775 section 4: an LCT header followed by the FEC Payload ID and
+/// encoding symbol of an `EncodingPacket`, using FEC Encoding ID 6.
+///
+/// Packets without an encoding symbol may be sent to signal the closing of a session or object.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AlcPacket {
+    header: LctHeader,
+    packet: Option<EncodingPacket>,
+}
+
+impl AlcPacket {
+    pub fn new(header: LctHeader, packet: Option<EncodingPacket>) -> AlcPacket {
+        AlcPacket { header, packet }
+    }
+
+    pub fn header(&self) -> &LctHeader {
+        &self.header
+    }
+
+    pub fn packet(&self) -> Option<&EncodingPacket> {
+        self.packet.as_ref()
+    }
+
+    pub fn split(self) -> (LctHeader, Option<EncodingPacket>) {
+        (self.header, self.packet)
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let payload_len = self
+            .packet
+            .as_ref()
+            .map_or(0, |packet| 4 + packet.data().len());
+        let mut serialized = Vec::with_capacity(self.header.serialized_len() + payload_len);
+        self.header.serialize_into(&mut serialized);
+        if let Some(ref packet) = self.packet {
+            serialized.extend_from_slice(&packet.payload_id().serialize());
+            serialized.extend_from_slice(packet.data());
+        }
+        serialized
+    }
+
+    pub fn deserialize(data: &[u8]) -> Result<AlcPacket, AlcError> {
+        let (header, header_len) = LctHeader::deserialize(data)?;
+        let payload = &data[header_len..];
+        let packet = match payload.len() {
+            0 => None,
+            _ => Some(
+                EncodingPacket::try_deserialize(payload).map_err(|_| AlcError::TruncatedPayload)?,
+            ),
+        };
+        Ok(AlcPacket { header, packet })
+    }
+}
+
+/// Sends objects over an ALC session, by wrapping the packets of an `Encoder` in LCT headers.
+pub struct AlcSender {
+    tsi: u64,
+    codepoint: u8,
+    include_oti: bool,
+}
+
+impl AlcSender {
+    pub fn new(tsi: u64) -> AlcSender {
+        assert!(tsi < 1 << 48, "TSI must fit in 48 bits");
+        AlcSender {
+            tsi,
+            codepoint: 0,
+            include_oti: true,
+        }
+    }
+
+    /// Sets the codepoint of every packet. Defaults to zero.
+    pub fn set_codepoint(&mut self, codepoint: u8) {
+        self.codepoint = codepoint;
+    }
+
+    /// Sets whether every packet carries the object transmission information in an EXT_FTI
+    /// header extension. Defaults to true. Disable it if the receivers learn the object
+    /// transmission information elsewhere, such as from a FLUTE FDT.
+    pub fn set_include_oti(&mut self, include_oti: bool) {
+        self.include_oti = include_oti;
+    }
+
+    fn header(&self, toi: u64) -> LctHeader {
+        let mut header = LctHeader::new(self.tsi, toi);
+        header.set_codepoint(self.codepoint);
+        header
+    }
+
+    /// Returns the serialized ALC packets of object `toi`: the source packets of every block,
+    /// each followed by `repair_packets_per_block` repair packets. The last packet has the Close
+    /// Object flag set.
+    pub fn object_packets(
+        &self,
+        toi: u64,
+        encoder: &Encoder,
+        repair_packets_per_block: u32,
+    ) -> Vec<Vec<u8>> {
+        let mut header = self.header(toi);
+        if self.include_oti {
+            header.set_oti(Some(encoder.get_config()));
+        }
+        let packets = encoder.get_encoded_packets(repair_packets_per_block);
+        let count = packets.len();
+        packets
+            .into_iter()
+            .enumerate()
+            .map(|(i, packet)| {
+                let mut header = header.clone();
+                header.set_close_object(i + 1 == count);
+                AlcPacket::new(header, Some(packet)).serialize()
+            })
+            .collect()
+    }
+
+    /// Returns a packet without an encoding symbol, which has the Close Session flag set.
+    pub fn close_session_packet(&self) -> Vec<u8> {
+        let mut header = self.header(0);
+        header.set_close_session(true);
+        AlcPacket::new(header, None).serialize()
+    }
+}
+
+enum ObjectState {
+    // Packets received before the object transmission information
+    AwaitingConfig(Vec<EncodingPacket>),
+    Decoding(Decoder),
+    Complete,
+}
+
+struct TrackedObject {
+    state: ObjectState,
+    // Value of AlcReceiver::clock when the object last received a packet
+    last_used: u64,
+}
+
+/// Receives the objects of an ALC session, demultiplexing packets by TOI into one `Decoder` per
+/// object.
+///
+/// The object transmission information of each object is taken from the EXT_FTI header
+/// extension, or from `set_object_config`. Packets received before it is known are buffered.
+///
+/// Since any sender can announce new TOIs, the receiver tracks at most `set_max_objects` objects,
+/// evicting the one which received a packet least recently to make room for a new one, and
+/// buffers at most `set_max_buffered_packets` packets of each object whose configuration is
+/// unknown, dropping any further packets.
+pub struct AlcReceiver {
+    tsi: u64,
+    objects: HashMap<u64, TrackedObject>,
+    session_closed: bool,
+    max_objects: usize,
+    max_buffered_packets: usize,
+    clock: u64,
+}
+
+impl AlcReceiver {
+    /// Creates a receiver for session `tsi`. Packets of other sessions are ignored.
+    pub fn new(tsi: u64) -> AlcReceiver {
+        AlcReceiver {
+            tsi,
+            objects: HashMap::new(),
+            session_closed: false,
+            max_objects: 64,
+            max_buffered_packets: 1024,
+            clock: 0,
+        }
+    }
+
+    /// Sets the maximum number of objects tracked at once, including completed objects. Defaults
+    /// to 64.
+    pub fn set_max_objects(&mut self, max_objects: usize) {
+        assert!(max_objects > 0);
+        self.max_objects = max_objects;
+        while self.objects.len() > self.max_objects {
+            self.evict_least_recently_used();
+        }
+    }
+
+    /// Sets the maximum number of packets buffered for each object whose object transmission
+    /// information is not known yet. Defaults to 1024.
+    pub fn set_max_buffered_packets(&mut self, max_buffered_packets: usize) {
+        self.max_buffered_packets = max_buffered_packets;
+        for object in self.objects.values_mut() {
+            if let ObjectState::AwaitingConfig(buffered) = &mut object.state {
+                buffered.truncate(max_buffered_packets);
+            }
+        }
+    }
+
+    /// Whether a packet with the Close Session flag has been received.
+    pub fn is_session_closed(&self) -> bool {
+        self.session_closed
+    }
+
+    /// Whether object `toi` has been decoded.
+    pub fn is_complete(&self, toi: u64) -> bool {
+        matches!(
+            self.objects.get(&toi),
+            Some(TrackedObject {
+                state: ObjectState::Complete,
+                ..
+            })
+        )
+    }
+
+    /// Stops tracking object `toi`, freeing its decoder and buffered packets, for example once
+    /// the application has consumed it. Returns whether the object was tracked. Packets of the
+    /// object received afterwards start a new object.
+    pub fn remove_object(&mut self, toi: u64) -> bool {
+        self.objects.remove(&toi).is_some()
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        if let Some(&toi) = self
+            .objects
+            .iter()
+            .min_by_key(|(_, object)| object.last_used)
+            .map(|(toi, _)| toi)
+        {
+            self.objects.remove(&toi);
+        }
+    }
+
+    // Returns the state of object `toi`, starting to track it if necessary
+    fn object_state(&mut self, toi: u64) -> &mut ObjectState {
+        if !self.objects.contains_key(&toi) && self.objects.len() >= self.max_objects {
+            self.evict_least_recently_used();
+        }
+        self.clock += 1;
+        let object = self.objects.entry(toi).or_insert_with(|| TrackedObject {
+            state: ObjectState::AwaitingConfig(vec![]),
+            last_used: 0,
+        });
+        object.last_used = self.clock;
+        &mut object.state
+    }
+
+    /// Provides the object transmission information of object `toi`, for example from a FLUTE
+    /// FDT. Returns the object if the packets already received are enough to decode it.
+    pub fn set_object_config(
+        &mut self,
+        toi: u64,
+        config: ObjectTransmissionInformation,
+    ) -> Option<Vec<u8>> {
+        let state = self.object_state(toi);
+        if let ObjectState::AwaitingConfig(buffered) = state {
+            let buffered = core::mem::take(buffered);
+            let mut decoder = Decoder::new(config);
+            for packet in buffered {
+                if let Ok(Some(result)) = decoder.try_decode(packet) {
+                    *state = ObjectState::Complete;
+                    return Some(result);
+                }
+            }
+            *state = ObjectState::Decoding(decoder);
+        }
+        None
+    }
+
+    /// Processes a serialized ALC packet, and returns the TOI and contents of the object it
+    /// completes, if any.
+    ///
+    /// Packets which cannot be parsed, or which the decoder of their object rejects, are
+    /// reported as errors and otherwise ignored.
+    pub fn receive(&mut self, data: &[u8]) -> Result<Option<(u64, Vec<u8>)>, AlcError> {
+        let (header, packet) = AlcPacket::deserialize(data)?.split();
+        if header.tsi() != self.tsi {
+            return Ok(None);
+        }
+        if header.close_session() {
+            self.session_closed = true;
+        }
+        let Some(packet) = packet else {
+            return Ok(None);
+        };
+
+        let toi = header.toi();
+        if let Some(config) = header.oti()
+            && let Some(result) = self.set_object_config(toi, config)
+        {
+            return Ok(Some((toi, result)));
+        }
+
+        let max_buffered_packets = self.max_buffered_packets;
+        let state = self.object_state(toi);
+        match state {
+            ObjectState::AwaitingConfig(buffered) => {
+                if buffered.len() < max_buffered_packets {
+                    buffered.push(packet);
+                }
+                Ok(None)
+            }
+            ObjectState::Decoding(decoder) => match decoder.try_decode(packet) {
+                Ok(Some(result)) => {
+                    *state = ObjectState::Complete;
+                    Ok(Some((toi, result)))
+                }
+                Ok(None) | Err(DecodeError::AlreadyDecoded) => Ok(None),
+                Err(err) => Err(AlcError::Decode(err)),
+            },
+            ObjectState::Complete => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use rand::seq::SliceRandom;
+    use std::vec::Vec;
+
+    use crate::alc::{EXT_NOP, ObjectState, TrackedObject};
+    use crate::{
+        AlcError, AlcPacket, AlcReceiver, AlcSender, Encoder, EncodingPacket, HeaderExtension,
+        LctHeader, ObjectTransmissionInformation, PayloadId,
+    };
+
+    #[test]
+    fn lct_header_layout() {
+        let mut header = LctHeader::new(0x1234, 0x56789A);
+        header.set_codepoint(6);
+        header.set_close_object(true);
+        let packet = AlcPacket::new(
+            header,
+            Some(EncodingPacket::new(PayloadId::new(1, 2), vec![7])),
+        );
+        assert_eq!(
+            packet.serialize(),
+            [
+                // V = 1, C = 0, PSI = 0 | S = 1, O = 1, H = 0, A = 0, B = 1 | HDR_LEN | CP
+                0x10, 0xA1, 4, 6, //
+                0, 0, 0, 0, // CCI
+                0, 0, 0x12, 0x34, // TSI
+                0, 0x56, 0x78, 0x9A, // TOI
+                1, 0, 0, 2, // FEC Payload ID
+                7
+            ]
+        );
+
+        // 16 bit TSI and TOI share the half-word flag
+        let header = LctHeader::new(1, 2);
+        assert_eq!(
+            header.serialize(),
+            [0x10, 0x10, 3, 0, 0, 0, 0, 0, 0, 1, 0, 2]
+        );
+    }
+
+    #[test]
+    fn lct_header_round_trip() {
+        let oti = ObjectTransmissionInformation::with_defaults(100_000, 1400);
+        for (tsi, toi) in [(0, 0), (1 << 20, 1), (1 << 40, 1 << 50), (7, u64::MAX)] {
+            let mut header = LctHeader::new(tsi, toi);
+            header.set_codepoint(rand::rng().random());
+            header.set_congestion_control(rand::rng().random());
+            header.set_protocol_specific(2);
+            header.set_close_session(rand::rng().random());
+            header.set_close_object(rand::rng().random());
+            header.set_oti(Some(oti));
+            header.add_extension(HeaderExtension::new(EXT_NOP, vec![1, 2, 3, 4, 5]));
+            header.add_extension(HeaderExtension::new(200, vec![9, 8, 7]));
+            let serialized = header.serialize();
+            assert_eq!(serialized.len() % 4, 0);
+            let (deserialized, len) = LctHeader::deserialize(&serialized).unwrap();
+            assert_eq!(len, serialized.len());
+            // Padding is added to the variable length extension
+            assert_eq!(deserialized.extensions()[0].content(), &[1, 2, 3, 4, 5, 0]);
+            assert_eq!(deserialized.extensions()[1], header.extensions()[1]);
+            assert_eq!(
+                (deserialized.tsi(), deserialized.toi()),
+                (header.tsi(), header.toi())
+            );
+            assert_eq!(deserialized.oti(), Some(oti));
+            assert_eq!(deserialized.codepoint(), header.codepoint());
+            assert_eq!(
+                deserialized.congestion_control(),
+                header.congestion_control()
+            );
+            assert_eq!(deserialized.protocol_specific(), 2);
+            assert_eq!(deserialized.close_session(), header.close_session());
+            assert_eq!(deserialized.close_object(), header.close_object());
+        }
+    }
+
+    #[test]
+    fn lct_header_rejects_malformed() {
+        let serialized = LctHeader::new(1, 2).serialize();
+        assert_eq!(
+            LctHeader::deserialize(&serialized[..11]),
+            Err(AlcError::Truncated)
+        );
+        let mut bad_version = serialized.clone();
+        bad_version[0] = 0x20;
+        assert_eq!(
+            LctHeader::deserialize(&bad_version),
+            Err(AlcError::UnsupportedVersion(2))
+        );
+        // Extension with a zero HEL
+        let mut bad_extension = serialized.clone();
+        bad_extension[2] += 1;
+        bad_extension.extend_from_slice(&[EXT_NOP, 0, 0, 0]);
+        assert_eq!(
+            LctHeader::deserialize(&bad_extension),
+            Err(AlcError::InvalidHeaderExtension(EXT_NOP))
+        );
+        let mut truncated_payload = serialized;
+        truncated_payload.extend_from_slice(&[1, 0]);
+        assert_eq!(
+            AlcPacket::deserialize(&truncated_payload),
+            Err(AlcError::TruncatedPayload)
+        );
+    }
+
+    #[test]
+    fn sender_and_receiver() {
+        let objects: Vec<Vec<u8>> = [20_000, 5_000]
+            .iter()
+            .map(|size| (0..*size).map(|_| rand::rng().random()).collect())
+            .collect();
+        let sender = AlcSender::new(42);
+        let mut packets = vec![];
+        for (toi, object) in objects.iter().enumerate() {
+            let encoder = Encoder::with_defaults(object, 1000);
+            let mut object_packets = sender.object_packets(toi as u64 + 1, &encoder, 10);
+            assert!(
+                AlcPacket::deserialize(object_packets.last().unwrap())
+                    .unwrap()
+                    .header()
+                    .close_object()
+            );
+            object_packets.shuffle(&mut rand::rng());
+            object_packets.truncate(object_packets.len() - 8);
+            packets.extend(object_packets);
+        }
+        packets.shuffle(&mut rand::rng());
+        // Packets of another session are ignored
+        packets.insert(0, AlcSender::new(7).close_session_packet());
+        packets.push(sender.close_session_packet());
+
+        let mut receiver = AlcReceiver::new(42);
+        let mut received = [None, None];
+        for packet in packets.iter() {
+            if let Some((toi, object)) = receiver.receive(packet).unwrap() {
+                assert!(received[toi as usize - 1].is_none());
+                received[toi as usize - 1] = Some(object);
+            }
+        }
+        assert!(receiver.is_session_closed());
+        assert!(receiver.is_complete(1) && receiver.is_complete(2));
+        assert_eq!(received[0].as_ref(), Some(&objects[0]));
+        assert_eq!(received[1].as_ref(), Some(&objects[1]));
+    }
+
+    #[test]
+    fn receiver_buffers_until_config() {
+        let data: Vec<u8> = (0..10_000).map(|_| rand::rng().random()).collect();
+        let encoder = Encoder::with_defaults(&data, 500);
+        let mut sender = AlcSender::new(1);
+        sender.set_include_oti(false);
+        let packets = sender.object_packets(3, &encoder, 0);
+
+        let mut receiver = AlcReceiver::new(1);
+        for packet in packets.iter() {
+            assert_eq!(receiver.receive(packet), Ok(None));
+        }
+        assert_eq!(
+            receiver.set_object_config(3, encoder.get_config()),
+            Some(data)
+        );
+        assert!(receiver.is_complete(3));
+    }
+
+    #[test]
+    fn receiver_limits() {
+        let data: Vec<u8> = (0..10_000).map(|_| rand::rng().random()).collect();
+        let encoder = Encoder::with_defaults(&data, 500);
+        let mut sender = AlcSender::new(1);
+        sender.set_include_oti(false);
+
+        let mut receiver = AlcReceiver::new(1);
+        receiver.set_max_objects(2);
+        receiver.set_max_buffered_packets(5);
+
+        // Only the first 5 packets of an object with an unknown configuration are kept
+        for packet in sender.object_packets(1, &encoder, 0).iter() {
+            assert_eq!(receiver.receive(packet), Ok(None));
+        }
+        let buffered = |receiver: &AlcReceiver, toi| match receiver.objects.get(&toi) {
+            Some(TrackedObject {
+                state: ObjectState::AwaitingConfig(buffered),
+                ..
+            }) => Some(buffered.len()),
+            _ => None,
+        };
+        assert_eq!(buffered(&receiver, 1), Some(5));
+
+        // Tracking a third object evicts the least recently used one
+        let first_packet = |toi| sender.object_packets(toi, &encoder, 0).remove(0);
+        assert_eq!(receiver.receive(&first_packet(2)), Ok(None));
+        assert_eq!(receiver.receive(&first_packet(1)), Ok(None));
+        assert_eq!(receiver.receive(&first_packet(3)), Ok(None));
+        assert_eq!(buffered(&receiver, 1), Some(5));
+        assert_eq!(buffered(&receiver, 2), None);
+        assert_eq!(buffered(&receiver, 3), Some(1));
+
+        assert!(receiver.remove_object(1));
+        assert!(!receiver.remove_object(1));
+        assert_eq!(buffered(&receiver, 1), None);
+
+        // Objects which have been removed can be received again
+        for packet in sender.object_packets(1, &encoder, 0).iter() {
+            assert_eq!(receiver.receive(packet), Ok(None));
+        }
+        receiver.set_max_buffered_packets(usize::MAX);
+        for packet in sender.object_packets(1, &encoder, 0).iter() {
+            assert_eq!(receiver.receive(packet), Ok(None));
+        }
+        assert_eq!(
+            receiver.set_object_config(1, encoder.get_config()),
+            Some(data)
+        );
+    }
+}
@@ -4,9 +4,13 @@ use std::{cmp::min, vec::Vec};
 #[cfg(not(feature = "std"))]
 use core::cmp::min;
 
+use core::fmt;
+
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 
+use crate::decoder::DecodeError;
+use crate::packet_auth::PacketSigner;
 use crate::rng::rand;
 use crate::systematic_constants::{
     MAX_SOURCE_SYMBOLS_PER_BLOCK, SYSTEMATIC_INDICES_AND_PARAMETERS,
@@ -82,6 +86,12 @@ impl EncodingPacket {
         }
     }
 
+    /// Like `deserialize`, but returns `DecodeError::TruncatedPacket` instead of panicking if
+    /// `data` is shorter than the payload ID.
+    pub fn try_deserialize(data: &[u8]) -> Result<EncodingPacket, DecodeError> {
+        EncodingPacketRef::try_deserialize(data).map(|packet| packet.to_owned_packet())
+    }
+
     pub fn serialize(&self) -> Vec<u8> {
         let mut serialized = Vec::with_capacity(4 + self.data.len());
         serialized.extend_from_slice(&self.payload_id.serialize());
@@ -89,6 +99,15 @@ impl EncodingPacket {
         return serialized;
     }
 
+    /// Serializes the packet followed by the trailer computed by `signer`, for a decoder which
+    /// verifies packets with `Decoder::try_decode_signed`.
+    pub fn serialize_signed(&self, signer: &dyn PacketSigner) -> Vec<u8> {
+        let mut serialized = self.serialize();
+        let trailer = signer.sign(&serialized);
+        serialized.extend_from_slice(&trailer);
+        serialized
+    }
+
     /// Retrieves packet payload ID.
     pub fn payload_id(&self) -> &PayloadId {
         &self.payload_id
@@ -105,6 +124,145 @@ impl EncodingPacket {
     }
 }
 
+/// Borrowed view of an encoding packet, for decoding without copying the payload.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EncodingPacketRef<'a> {
+    payload_id: PayloadId,
+    data: &'a [u8],
+}
+
+impl<'a> EncodingPacketRef<'a> {
+    pub fn new(payload_id: PayloadId, data: &'a [u8]) -> EncodingPacketRef<'a> {
+        EncodingPacketRef { payload_id, data }
+    }
+
+    /// Parses a serialized packet, as produced by `EncodingPacket::serialize`, without copying
+    /// its payload.
+    pub fn deserialize(data: &'a [u8]) -> EncodingPacketRef<'a> {
+        let payload_data = [data[0], data[1], data[2], data[3]];
+        EncodingPacketRef {
+            payload_id: PayloadId::deserialize(&payload_data),
+            data: &data[4..],
+        }
+    }
+
+    /// Like `deserialize`, but returns `DecodeError::TruncatedPacket` instead of panicking if
+    /// `data` is shorter than the payload ID.
+    pub fn try_deserialize(data: &'a [u8]) -> Result<EncodingPacketRef<'a>, DecodeError> {
+        match data.split_first_chunk::<4>() {
+            Some((payload_data, payload)) => Ok(EncodingPacketRef {
+                payload_id: PayloadId::deserialize(payload_data),
+                data: payload,
+            }),
+            None => Err(DecodeError::TruncatedPacket(data.len())),
+        }
+    }
+
+    /// Retrieves packet payload ID.
+    pub fn payload_id(&self) -> &PayloadId {
+        &self.payload_id
+    }
+
+    /// Retrieves packet payload.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Copies the payload into an owned packet.
+    pub fn to_owned_packet(&self) -> EncodingPacket {
+        EncodingPacket::new(self.payload_id.clone(), self.data.to_vec())
+    }
+}
+
+impl<'a> From<&'a EncodingPacket> for EncodingPacketRef<'a> {
+    fn from(packet: &'a EncodingPacket) -> EncodingPacketRef<'a> {
+        EncodingPacketRef::new(packet.payload_id.clone(), &packet.data)
+    }
+}
+
+// See errata (https://www.rfc-editor.org/errata/eid5548)
+const MAX_TRANSFER_LENGTH: u64 = 942574504275;
+
+/// Reason an `ObjectTransmissionInformation` violates the constraints of section 4.4.1.2.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ObjectTransmissionInformationError {
+    /// The transfer length exceeds the maximum supported object size.
+    TransferLengthTooLarge(u64),
+    /// The symbol size is zero.
+    ZeroSymbolSize,
+    /// The symbol alignment is zero.
+    ZeroSymbolAlignment,
+    /// The symbol size is not a multiple of the symbol alignment.
+    UnalignedSymbolSize { symbol_size: u16, alignment: u8 },
+    /// The number of source blocks is zero.
+    ZeroSourceBlocks,
+    /// There are more source blocks than source symbols, leaving some blocks empty.
+    TooManySourceBlocks {
+        source_blocks: u8,
+        source_symbols: u64,
+    },
+    /// The number of sub-blocks is zero.
+    ZeroSubBlocks,
+    /// There are more sub-blocks than aligned units in a symbol, leaving some sub-symbols empty.
+    TooManySubBlocks {
+        sub_blocks: u16,
+        max_sub_blocks: u16,
+    },
+    /// A source block would contain more than K'_max source symbols.
+    TooManySourceSymbols(u64),
+}
+
+impl fmt::Display for ObjectTransmissionInformationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjectTransmissionInformationError::TransferLengthTooLarge(length) => write!(
+                f,
+                "transfer length {length} exceeds maximum of {MAX_TRANSFER_LENGTH}"
+            ),
+            ObjectTransmissionInformationError::ZeroSymbolSize => {
+                write!(f, "symbol size must be non-zero")
+            }
+            ObjectTransmissionInformationError::ZeroSymbolAlignment => {
+                write!(f, "symbol alignment must be non-zero")
+            }
+            ObjectTransmissionInformationError::UnalignedSymbolSize {
+                symbol_size,
+                alignment,
+            } => write!(
+                f,
+                "symbol size {symbol_size} is not a multiple of alignment {alignment}"
+            ),
+            ObjectTransmissionInformationError::ZeroSourceBlocks => {
+                write!(f, "number of source blocks must be non-zero")
+            }
+            ObjectTransmissionInformationError::TooManySourceBlocks {
+                source_blocks,
+                source_symbols,
+            } => write!(
+                f,
+                "{source_blocks} source blocks exceeds the {source_symbols} source symbols"
+            ),
+            ObjectTransmissionInformationError::ZeroSubBlocks => {
+                write!(f, "number of sub-blocks must be non-zero")
+            }
+            ObjectTransmissionInformationError::TooManySubBlocks {
+                sub_blocks,
+                max_sub_blocks,
+            } => write!(
+                f,
+                "{sub_blocks} sub-blocks exceeds maximum of {max_sub_blocks}"
+            ),
+            ObjectTransmissionInformationError::TooManySourceSymbols(symbols) => write!(
+                f,
+                "{symbols} source symbols per block exceeds maximum of {MAX_SOURCE_SYMBOLS_PER_BLOCK}"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ObjectTransmissionInformationError {}
+
 // As defined in section 3.3.2 and 3.3.3
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
 #[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
@@ -124,8 +282,7 @@ impl ObjectTransmissionInformation {
         sub_blocks: u16,
         alignment: u8,
     ) -> ObjectTransmissionInformation {
-        // See errata (https://www.rfc-editor.org/errata/eid5548)
-        assert!(transfer_length <= 942574504275);
+        assert!(transfer_length <= MAX_TRANSFER_LENGTH);
         assert_eq!(symbol_size % alignment as u16, 0);
         // See section 4.4.1.2. "These parameters MUST be set so that ceil(ceil(F/T)/Z) <= K'_max."
 
@@ -146,6 +303,77 @@ impl ObjectTransmissionInformation {
         }
     }
 
+    /// Like `new`, but returns an error unless all constraints of section 4.4.1.2 are met.
+    pub fn try_new(
+        transfer_length: u64,
+        symbol_size: u16,
+        source_blocks: u8,
+        sub_blocks: u16,
+        alignment: u8,
+    ) -> Result<ObjectTransmissionInformation, ObjectTransmissionInformationError> {
+        let config = ObjectTransmissionInformation {
+            transfer_length,
+            symbol_size,
+            num_source_blocks: source_blocks,
+            num_sub_blocks: sub_blocks,
+            symbol_alignment: alignment,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks that the parameters satisfy the constraints of section 4.4.1.2, so that they can
+    /// safely be used to construct a `Decoder`.
+    pub fn validate(&self) -> Result<(), ObjectTransmissionInformationError> {
+        if self.transfer_length > MAX_TRANSFER_LENGTH {
+            return Err(ObjectTransmissionInformationError::TransferLengthTooLarge(
+                self.transfer_length,
+            ));
+        }
+        if self.symbol_size == 0 {
+            return Err(ObjectTransmissionInformationError::ZeroSymbolSize);
+        }
+        if self.symbol_alignment == 0 {
+            return Err(ObjectTransmissionInformationError::ZeroSymbolAlignment);
+        }
+        if !self
+            .symbol_size
+            .is_multiple_of(self.symbol_alignment as u16)
+        {
+            return Err(ObjectTransmissionInformationError::UnalignedSymbolSize {
+                symbol_size: self.symbol_size,
+                alignment: self.symbol_alignment,
+            });
+        }
+        if self.num_source_blocks == 0 {
+            return Err(ObjectTransmissionInformationError::ZeroSourceBlocks);
+        }
+        let source_symbols = self.transfer_length.div_ceil(self.symbol_size as u64);
+        if self.num_source_blocks as u64 > source_symbols {
+            return Err(ObjectTransmissionInformationError::TooManySourceBlocks {
+                source_blocks: self.num_source_blocks,
+                source_symbols,
+            });
+        }
+        if self.num_sub_blocks == 0 {
+            return Err(ObjectTransmissionInformationError::ZeroSubBlocks);
+        }
+        let max_sub_blocks = self.symbol_size / self.symbol_alignment as u16;
+        if self.num_sub_blocks > max_sub_blocks {
+            return Err(ObjectTransmissionInformationError::TooManySubBlocks {
+                sub_blocks: self.num_sub_blocks,
+                max_sub_blocks,
+            });
+        }
+        let symbols_per_block = source_symbols.div_ceil(self.num_source_blocks as u64);
+        if symbols_per_block > MAX_SOURCE_SYMBOLS_PER_BLOCK as u64 {
+            return Err(ObjectTransmissionInformationError::TooManySourceSymbols(
+                symbols_per_block,
+            ));
+        }
+        Ok(())
+    }
+
     pub fn deserialize(data: &[u8; 12]) -> ObjectTransmissionInformation {
         ObjectTransmissionInformation {
             transfer_length: ((data[0] as u64) << 32)
@@ -160,6 +388,15 @@ impl ObjectTransmissionInformation {
         }
     }
 
+    /// Like `deserialize`, but validates the decoded parameters.
+    pub fn try_deserialize(
+        data: &[u8; 12],
+    ) -> Result<ObjectTransmissionInformation, ObjectTransmissionInformationError> {
+        let config = ObjectTransmissionInformation::deserialize(data);
+        config.validate()?;
+        Ok(config)
+    }
+
     pub fn serialize(&self) -> [u8; 12] {
         [
             ((self.transfer_length >> 32) & 0xFF) as u8,
@@ -177,6 +414,44 @@ impl ObjectTransmissionInformation {
         ]
     }
 
+    /// Serializes the Common FEC Object Transmission Information element: the transfer length
+    /// and symbol size. See section 3.3.2.
+    pub fn serialize_common(&self) -> [u8; 8] {
+        let mut common = [0; 8];
+        common.copy_from_slice(&self.serialize()[..8]);
+        common
+    }
+
+    /// Serializes the Scheme-Specific FEC Object Transmission Information element: the number of
+    /// source blocks, number of sub-blocks, and symbol alignment. See section 3.3.3.
+    pub fn serialize_scheme_specific(&self) -> [u8; 4] {
+        let mut scheme_specific = [0; 4];
+        scheme_specific.copy_from_slice(&self.serialize()[8..]);
+        scheme_specific
+    }
+
+    /// Parses the Common and Scheme-Specific FEC Object Transmission Information elements, for
+    /// protocols which carry them separately.
+    pub fn deserialize_elements(
+        common: &[u8; 8],
+        scheme_specific: &[u8; 4],
+    ) -> ObjectTransmissionInformation {
+        let mut data = [0; 12];
+        data[..8].copy_from_slice(common);
+        data[8..].copy_from_slice(scheme_specific);
+        ObjectTransmissionInformation::deserialize(&data)
+    }
+
+    /// Like `deserialize_elements`, but validates the decoded parameters.
+    pub fn try_deserialize_elements(
+        common: &[u8; 8],
+        scheme_specific: &[u8; 4],
+    ) -> Result<ObjectTransmissionInformation, ObjectTransmissionInformationError> {
+        let config = ObjectTransmissionInformation::deserialize_elements(common, scheme_specific);
+        config.validate()?;
+        Ok(config)
+    }
+
     pub fn transfer_length(&self) -> u64 {
         self.transfer_length
     }
@@ -328,7 +603,10 @@ pub fn intermediate_tuple(
 
 #[cfg(test)]
 mod tests {
-    use crate::{EncodingPacket, ObjectTransmissionInformation, PayloadId};
+    use crate::{
+        DecodeError, EncodingPacket, EncodingPacketRef, ObjectTransmissionInformation,
+        ObjectTransmissionInformationError, PayloadId,
+    };
     use rand::Rng;
 
     #[test]
@@ -357,6 +635,43 @@ mod tests {
         assert_eq!(deserialized, packet);
     }
 
+    #[test]
+    fn encoding_packet_ref_deserialization() {
+        let payload_id = PayloadId::new(
+            rand::rng().random(),
+            rand::rng().random_range(0..(256 * 256 * 256)),
+        );
+        let packet = EncodingPacket::new(payload_id, vec![rand::rng().random(); 16]);
+        let serialized = packet.serialize();
+        let packet_ref = EncodingPacketRef::deserialize(&serialized);
+        assert_eq!(packet_ref, EncodingPacketRef::from(&packet));
+        assert_eq!(packet_ref.to_owned_packet(), packet);
+    }
+
+    #[test]
+    fn encoding_packet_try_deserialize() {
+        let packet = EncodingPacket::new(PayloadId::new(1, 2), vec![rand::rng().random(); 16]);
+        let serialized = packet.serialize();
+        assert_eq!(
+            EncodingPacket::try_deserialize(&serialized),
+            Ok(packet.clone())
+        );
+        assert_eq!(
+            EncodingPacketRef::try_deserialize(&serialized),
+            Ok(EncodingPacketRef::from(&packet))
+        );
+        for length in 0..4 {
+            assert_eq!(
+                EncodingPacket::try_deserialize(&serialized[..length]),
+                Err(DecodeError::TruncatedPacket(length))
+            );
+            assert_eq!(
+                EncodingPacketRef::try_deserialize(&serialized[..length]),
+                Err(DecodeError::TruncatedPacket(length))
+            );
+        }
+    }
+
     #[test]
     fn oti_serialization() {
         let oti = ObjectTransmissionInformation::with_defaults(
@@ -366,4 +681,103 @@ mod tests {
         let deserialized = ObjectTransmissionInformation::deserialize(&oti.serialize());
         assert_eq!(deserialized, oti);
     }
+
+    #[test]
+    fn oti_element_serialization() {
+        let oti = ObjectTransmissionInformation::with_defaults(
+            rand::rng().random_range(0..(256 * 256 * 256 * 256 * 256)),
+            rand::rng().random(),
+        );
+        let common = oti.serialize_common();
+        let scheme_specific = oti.serialize_scheme_specific();
+        assert_eq!(
+            [common.as_slice(), scheme_specific.as_slice()].concat(),
+            oti.serialize()
+        );
+        assert_eq!(
+            ObjectTransmissionInformation::deserialize_elements(&common, &scheme_specific),
+            oti
+        );
+
+        let oti = ObjectTransmissionInformation::new(0x0102030405, 0xFF00, 3, 5, 8);
+        assert_eq!(
+            oti.serialize_common(),
+            [0x01, 0x02, 0x03, 0x04, 0x05, 0x00, 0xFF, 0x00]
+        );
+        assert_eq!(oti.serialize_scheme_specific(), [0x03, 0x00, 0x05, 0x08]);
+        assert_eq!(
+            ObjectTransmissionInformation::try_deserialize_elements(
+                &oti.serialize_common(),
+                &[0, 0, 1, 8]
+            ),
+            Err(ObjectTransmissionInformationError::ZeroSourceBlocks)
+        );
+    }
+
+    #[test]
+    fn oti_try_deserialize() {
+        let oti = ObjectTransmissionInformation::with_defaults(
+            rand::rng().random_range(1..(1 << 30)),
+            rand::rng().random_range(1024..=u16::MAX),
+        );
+        assert_eq!(oti.validate(), Ok(()));
+        assert_eq!(
+            ObjectTransmissionInformation::try_deserialize(&oti.serialize()),
+            Ok(oti)
+        );
+        assert_eq!(
+            ObjectTransmissionInformation::try_deserialize(&[0; 12]),
+            Err(ObjectTransmissionInformationError::ZeroSymbolSize)
+        );
+    }
+
+    #[test]
+    fn oti_try_new() {
+        assert!(ObjectTransmissionInformation::try_new(942574504275, 65535, 255, 1, 1).is_ok());
+        assert_eq!(
+            ObjectTransmissionInformation::try_new(942574504276, 65535, 255, 1, 1),
+            Err(ObjectTransmissionInformationError::TransferLengthTooLarge(
+                942574504276
+            ))
+        );
+        assert_eq!(
+            ObjectTransmissionInformation::try_new(1000, 64, 1, 1, 0),
+            Err(ObjectTransmissionInformationError::ZeroSymbolAlignment)
+        );
+        assert_eq!(
+            ObjectTransmissionInformation::try_new(1000, 60, 1, 1, 8),
+            Err(ObjectTransmissionInformationError::UnalignedSymbolSize {
+                symbol_size: 60,
+                alignment: 8
+            })
+        );
+        assert_eq!(
+            ObjectTransmissionInformation::try_new(1000, 64, 0, 1, 8),
+            Err(ObjectTransmissionInformationError::ZeroSourceBlocks)
+        );
+        assert_eq!(
+            ObjectTransmissionInformation::try_new(1000, 64, 17, 1, 8),
+            Err(ObjectTransmissionInformationError::TooManySourceBlocks {
+                source_blocks: 17,
+                source_symbols: 16
+            })
+        );
+        assert_eq!(
+            ObjectTransmissionInformation::try_new(1000, 64, 1, 0, 8),
+            Err(ObjectTransmissionInformationError::ZeroSubBlocks)
+        );
+        assert_eq!(
+            ObjectTransmissionInformation::try_new(1000, 64, 1, 9, 8),
+            Err(ObjectTransmissionInformationError::TooManySubBlocks {
+                sub_blocks: 9,
+                max_sub_blocks: 8
+            })
+        );
+        assert_eq!(
+            ObjectTransmissionInformation::try_new(56404, 1, 1, 1, 1),
+            Err(ObjectTransmissionInformationError::TooManySourceSymbols(
+                56404
+            ))
+        );
+    }
 }
@@ -0,0 +1,326 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, ErrorKind, Seek};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use raptorq::{
+    ContainerReader, ContainerWriter, DecodeError, Decoder, EncoderBuilder, EncodingPacketRef,
+    ObjectChecksum, ObjectTransmissionInformation, StreamingEncoder, extended_source_block_symbols,
+    num_hdpc_symbols, num_intermediate_symbols, num_ldpc_symbols, partition,
+};
+
+const USAGE: &str = "\
+Usage:
+  raptorq encode [options] <input file> <output>
+  raptorq decode <input> <output file>
+  raptorq info <input>
+
+encode options:
+  --mtu <bytes>            Maximum packet payload size (default 1400)
+  --repair <count>         Repair packets to generate per source block (default 10)
+  --memory-limit <bytes>   Decoder working memory available, used to size source blocks
+                           (default 10485760)
+  --single                 Write a single file of packets, instead of a directory
+
+An encoded object is either a directory containing an `object.oti` file and one file per
+packet, or a single container file (see `ContainerWriter`). Packets may be removed from either,
+and decoding succeeds as long as enough remain.";
+
+const OTI_FILE_NAME: &str = "object.oti";
+// Smallest extended source block of RFC 6330, which the decoder memory must hold at least once
+const MIN_BLOCK_SYMBOLS: u64 = 10;
+const PACKET_EXTENSION: &str = "packet";
+
+struct EncodeOptions {
+    mtu: u16,
+    repair_packets: u32,
+    memory_limit: u64,
+    single: bool,
+    input: PathBuf,
+    output: PathBuf,
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{flag} requires a value"))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value for {flag}: {value}"))
+}
+
+fn parse_encode_options<I: Iterator<Item = String>>(mut args: I) -> Result<EncodeOptions, String> {
+    let mut mtu = 1400;
+    let mut repair_packets = 10;
+    let mut memory_limit = 10 * 1024 * 1024;
+    let mut single = false;
+    let mut paths = vec![];
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--mtu" => mtu = parse_value(&arg, args.next())?,
+            "--repair" => repair_packets = parse_value(&arg, args.next())?,
+            "--memory-limit" => memory_limit = parse_value(&arg, args.next())?,
+            "--single" => single = true,
+            _ if arg.starts_with("--") => return Err(format!("unknown option {arg}")),
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    let [input, output] = <[PathBuf; 2]>::try_from(paths)
+        .map_err(|_| "encode takes an input file and an output".to_string())?;
+    if mtu <= 4 {
+        return Err("--mtu must be greater than 4".to_string());
+    }
+    if memory_limit < MIN_BLOCK_SYMBOLS * mtu as u64 {
+        return Err(format!(
+            "--memory-limit must be at least {} bytes, {MIN_BLOCK_SYMBOLS} times the --mtu",
+            MIN_BLOCK_SYMBOLS * mtu as u64
+        ));
+    }
+    Ok(EncodeOptions {
+        mtu,
+        repair_packets,
+        memory_limit,
+        single,
+        input,
+        output,
+    })
+}
+
+fn paths<const N: usize, I: Iterator<Item = String>>(
+    command: &str,
+    args: I,
+) -> Result<[PathBuf; N], String> {
+    let paths: Vec<PathBuf> = args.map(PathBuf::from).collect();
+    <[PathBuf; N]>::try_from(paths).map_err(|_| format!("wrong number of arguments to {command}"))
+}
+
+// Encodes the input one source block at a time, so that it never has to fit in memory
+fn encode(options: EncodeOptions) -> io::Result<()> {
+    let mut input = File::open(&options.input)?;
+    let transfer_length = input.metadata()?.len();
+    if transfer_length == 0 {
+        return Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            "cannot encode an empty input file",
+        ));
+    }
+    let mut builder = EncoderBuilder::new();
+    builder.set_max_packet_size(options.mtu);
+    builder.set_decoder_memory_requirement(options.memory_limit);
+    let config = builder.build_config(transfer_length);
+
+    let mut container = if options.single {
+        let object_checksum = ObjectChecksum::crc32c_reader(BufReader::new(&mut input))?;
+        input.rewind()?;
+        let writer = BufWriter::new(File::create(&options.output)?);
+        Some(ContainerWriter::new(writer, config, &object_checksum)?)
+    } else {
+        fs::create_dir_all(&options.output)?;
+        fs::write(options.output.join(OTI_FILE_NAME), config.serialize())?;
+        None
+    };
+
+    let mut encoder = StreamingEncoder::new(BufReader::new(input), config);
+    while let Some(packets) = encoder.next_block_packets(options.repair_packets)? {
+        for packet in packets {
+            match container {
+                Some(ref mut writer) => writer.write_packet(&packet)?,
+                None => {
+                    let name = format!(
+                        "{}_{}.{PACKET_EXTENSION}",
+                        packet.payload_id().source_block_number(),
+                        packet.payload_id().encoding_symbol_id()
+                    );
+                    fs::write(options.output.join(name), packet.serialize())?;
+                }
+            }
+        }
+    }
+    if let Some(writer) = container {
+        writer.finish()?;
+    }
+    eprintln!(
+        "encoded {} bytes into {} source blocks",
+        config.transfer_length(),
+        config.source_blocks()
+    );
+    Ok(())
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message)
+}
+
+fn read_oti(data: &[u8]) -> io::Result<ObjectTransmissionInformation> {
+    let oti: &[u8; 12] = data
+        .get(..12)
+        .and_then(|oti| oti.try_into().ok())
+        .ok_or_else(|| invalid_data("truncated object transmission information".to_string()))?;
+    ObjectTransmissionInformation::try_deserialize(oti)
+        .map_err(|err| invalid_data(format!("invalid object transmission information: {err}")))
+}
+
+// Reads the configuration and serialized packets of a directory of packets
+fn read_packet_directory(path: &Path) -> io::Result<(ObjectTransmissionInformation, Vec<Vec<u8>>)> {
+    let config = read_oti(&fs::read(path.join(OTI_FILE_NAME))?)?;
+    let mut packets = vec![];
+    for entry in fs::read_dir(path)? {
+        let entry_path = entry?.path();
+        if entry_path
+            .extension()
+            .is_some_and(|ext| ext == PACKET_EXTENSION)
+        {
+            packets.push(fs::read(entry_path)?);
+        }
+    }
+    Ok((config, packets))
+}
+
+fn decode_directory(input: &Path) -> io::Result<Option<Vec<u8>>> {
+    let (config, packets) = read_packet_directory(input)?;
+    let mut decoder = Decoder::new(config);
+    let mut skipped = 0;
+    for packet in packets.iter() {
+        match EncodingPacketRef::try_deserialize(packet)
+            .and_then(|packet| decoder.try_decode_ref(packet))
+        {
+            Ok(Some(result)) => {
+                if skipped > 0 {
+                    eprintln!("skipped {skipped} malformed packets");
+                }
+                return Ok(Some(result));
+            }
+            Ok(None) | Err(DecodeError::AlreadyDecoded) => {}
+            Err(_) => skipped += 1,
+        }
+    }
+    eprintln!(
+        "read {} packets, of which {skipped} were malformed",
+        packets.len()
+    );
+    Ok(None)
+}
+
+fn decode_container(input: &Path) -> io::Result<Option<Vec<u8>>> {
+    let mut reader = ContainerReader::new(BufReader::new(File::open(input)?))?;
+    let result = reader.decode()?;
+    if reader.skipped_records() > 0 {
+        eprintln!("skipped {} corrupt records", reader.skipped_records());
+    }
+    Ok(result)
+}
+
+fn decode(input: &Path, output: &Path) -> io::Result<()> {
+    let result = if input.is_dir() {
+        decode_directory(input)?
+    } else {
+        decode_container(input)?
+    };
+    match result {
+        Some(result) => fs::write(output, result),
+        None => Err(invalid_data("not enough packets to decode".to_string())),
+    }
+}
+
+fn info(input: &Path) -> io::Result<()> {
+    let config = if input.is_dir() {
+        read_packet_directory(input)?.0
+    } else {
+        match ContainerReader::new(File::open(input)?) {
+            Ok(reader) => reader.get_config(),
+            // Also accept a bare 12 byte OTI
+            Err(_) => read_oti(&fs::read(input)?)?,
+        }
+    };
+    println!("transfer length:  {}", config.transfer_length());
+    println!("symbol size:      {}", config.symbol_size());
+    println!("source blocks:    {}", config.source_blocks());
+    println!("sub-blocks:       {}", config.sub_blocks());
+    println!("symbol alignment: {}", config.symbol_alignment());
+
+    let total_symbols = config
+        .transfer_length()
+        .div_ceil(config.symbol_size() as u64) as u32;
+    let (kl, ks, zl, zs) = partition(total_symbols, config.source_blocks());
+    println!();
+    println!(
+        "{:>5} {:>6} {:>6} {:>5} {:>5} {:>6}",
+        "block", "K", "K'", "S", "H", "L"
+    );
+    for block in 0..(zl + zs) {
+        let k = if block < zl { kl } else { ks };
+        println!(
+            "{:>5} {:>6} {:>6} {:>5} {:>5} {:>6}",
+            block,
+            k,
+            extended_source_block_symbols(k),
+            num_ldpc_symbols(k),
+            num_hdpc_symbols(k),
+            num_intermediate_symbols(k)
+        );
+    }
+    Ok(())
+}
+
+fn run() -> Result<(), String> {
+    let mut args = env::args().skip(1);
+    let command = args.next().ok_or_else(|| USAGE.to_string())?;
+    let result = match command.as_str() {
+        "encode" => encode(parse_encode_options(args)?),
+        "decode" => {
+            let [input, output] = paths("decode", args)?;
+            decode(&input, &output)
+        }
+        "info" => {
+            let [input] = paths("info", args)?;
+            info(&input)
+        }
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => return Err(format!("unknown command {command}\n\n{USAGE}")),
+    };
+    result.map_err(|err| err.to_string())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::parse_encode_options;
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn encode_options() {
+        let options = parse_encode_options(args(&[
+            "--mtu", "1000", "in", "--repair", "3", "--single", "out",
+        ]))
+        .unwrap();
+        assert_eq!(options.mtu, 1000);
+        assert_eq!(options.repair_packets, 3);
+        assert_eq!(options.memory_limit, 10 * 1024 * 1024);
+        assert!(options.single);
+        assert_eq!(options.input, PathBuf::from("in"));
+        assert_eq!(options.output, PathBuf::from("out"));
+
+        assert!(parse_encode_options(args(&["in"])).is_err());
+        assert!(parse_encode_options(args(&["--mtu", "x", "in", "out"])).is_err());
+        assert!(parse_encode_options(args(&["--bogus", "in", "out"])).is_err());
+    }
+}
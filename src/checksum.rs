@@ -0,0 +1,190 @@
+#[cfg(feature = "std")]
+use std::io::{self, ErrorKind, Read};
+#[cfg(feature = "std")]
+use std::{sync::Arc, vec::Vec};
+
+#[cfg(not(feature = "std"))]
+use alloc::{sync::Arc, vec::Vec};
+
+use core::fmt;
+
+use crate::util::crc32c;
+#[cfg(feature = "std")]
+use crate::util::crc32c_extend;
+#[cfg(feature = "serde_support")]
+use serde::{Deserialize, Serialize};
+
+/// Algorithm ID of the built-in CRC-32C checksum. IDs 128 and above are reserved for custom
+/// hashers, see `ObjectChecksum::with_hasher`.
+pub const CRC32C_ALGORITHM: u8 = 1;
+
+/// Hash function used to compute an `ObjectChecksum`.
+pub trait ObjectHasher: Send + Sync {
+    fn digest(&self, data: &[u8]) -> Vec<u8>;
+}
+
+// Lets Decoder keep deriving Clone, Debug, PartialEq and Eq while holding a hasher
+#[derive(Clone)]
+pub(crate) struct SharedObjectHasher(pub(crate) Arc<dyn ObjectHasher>);
+
+impl fmt::Debug for SharedObjectHasher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ObjectHasher")
+    }
+}
+
+impl PartialEq for SharedObjectHasher {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for SharedObjectHasher {}
+
+/// CRC-32C (Castagnoli), stored as a big-endian u32.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Crc32c;
+
+impl ObjectHasher for Crc32c {
+    fn digest(&self, data: &[u8]) -> Vec<u8> {
+        crc32c(data).to_be_bytes().to_vec()
+    }
+}
+
+/// Checksum of an entire object, which the sender transmits along with the
+/// `ObjectTransmissionInformation` so that the receiver can detect corrupted symbols.
+///
+/// See `Decoder::with_checksum`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct ObjectChecksum {
+    algorithm: u8,
+    digest: Vec<u8>,
+}
+
+impl ObjectChecksum {
+    /// Computes the CRC-32C checksum of `data`.
+    pub fn crc32c(data: &[u8]) -> ObjectChecksum {
+        ObjectChecksum {
+            algorithm: CRC32C_ALGORITHM,
+            digest: Crc32c.digest(data),
+        }
+    }
+
+    /// Computes the CRC-32C checksum of everything remaining in `reader`, without holding it in
+    /// memory.
+    #[cfg(feature = "std")]
+    pub fn crc32c_reader<R: Read>(mut reader: R) -> io::Result<ObjectChecksum> {
+        let mut crc = 0;
+        let mut buffer = vec![0; 64 * 1024];
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(n) => crc = crc32c_extend(crc, &buffer[..n]),
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(ObjectChecksum {
+            algorithm: CRC32C_ALGORITHM,
+            digest: crc.to_be_bytes().to_vec(),
+        })
+    }
+
+    /// Computes the checksum of `data` using a custom hasher. The receiver must verify it with the
+    /// same hasher, passed to `Decoder::set_object_hasher` or `ContainerReader::set_object_hasher`.
+    pub fn with_hasher(algorithm: u8, hasher: &dyn ObjectHasher, data: &[u8]) -> ObjectChecksum {
+        assert!(
+            algorithm >= 128,
+            "custom algorithm IDs must be at least 128"
+        );
+        let digest = hasher.digest(data);
+        assert!(digest.len() <= u8::MAX as usize);
+        ObjectChecksum { algorithm, digest }
+    }
+
+    pub fn algorithm(&self) -> u8 {
+        self.algorithm
+    }
+
+    pub fn digest(&self) -> &[u8] {
+        &self.digest
+    }
+
+    /// Serializes the checksum as the algorithm ID, the digest length, and the digest.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut serialized = Vec::with_capacity(2 + self.digest.len());
+        serialized.push(self.algorithm);
+        serialized.push(self.digest.len() as u8);
+        serialized.extend_from_slice(&self.digest);
+        serialized
+    }
+
+    /// Parses a checksum produced by `serialize`, returning it along with the number of bytes
+    /// consumed, or `None` if `data` is truncated.
+    pub fn deserialize(data: &[u8]) -> Option<(ObjectChecksum, usize)> {
+        let (&algorithm, rest) = data.split_first()?;
+        let (&length, rest) = rest.split_first()?;
+        let digest = rest.get(..length as usize)?.to_vec();
+        Some((ObjectChecksum { algorithm, digest }, 2 + length as usize))
+    }
+
+    // Returns whether data matches the checksum, or None if it uses a custom algorithm and no
+    // hasher was given for it
+    pub(crate) fn matches(&self, data: &[u8], hasher: Option<&dyn ObjectHasher>) -> Option<bool> {
+        if self.algorithm == CRC32C_ALGORITHM {
+            return Some(Crc32c.digest(data) == self.digest);
+        }
+        if self.algorithm < 128 {
+            return None;
+        }
+        Some(hasher?.digest(data) == self.digest)
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use crate::{ObjectChecksum, ObjectHasher};
+
+    struct XorHasher;
+
+    impl ObjectHasher for XorHasher {
+        fn digest(&self, data: &[u8]) -> Vec<u8> {
+            vec![data.iter().fold(0, |acc, byte| acc ^ byte)]
+        }
+    }
+
+    #[test]
+    fn checksum_serialization() {
+        let checksum = ObjectChecksum::crc32c(b"123456789");
+        assert_eq!(checksum.digest(), &0xE3069283u32.to_be_bytes());
+        assert_eq!(
+            ObjectChecksum::crc32c_reader(&b"123456789"[..]).unwrap(),
+            checksum
+        );
+        let serialized = checksum.serialize();
+        assert_eq!(
+            ObjectChecksum::deserialize(&serialized),
+            Some((checksum, serialized.len()))
+        );
+        assert_eq!(
+            ObjectChecksum::deserialize(&serialized[..serialized.len() - 1]),
+            None
+        );
+    }
+
+    #[test]
+    fn custom_hasher() {
+        let checksum = ObjectChecksum::with_hasher(200, &XorHasher, &[1, 2, 4]);
+        assert_eq!(checksum.digest(), &[7]);
+        assert_eq!(checksum.matches(&[1, 2, 4], None), None);
+        assert_eq!(checksum.matches(&[1, 2, 4], Some(&XorHasher)), Some(true));
+        assert_eq!(checksum.matches(&[1, 2, 5], Some(&XorHasher)), Some(false));
+        // The built-in algorithm ignores the hasher
+        let crc = ObjectChecksum::crc32c(&[1, 2, 4]);
+        assert_eq!(crc.matches(&[1, 2, 4], Some(&XorHasher)), Some(true));
+    }
+}
@@ -8,7 +8,9 @@ use crate::base::intermediate_tuple;
 use crate::matrix::BinaryMatrix;
 use crate::octet::Octet;
 use crate::octet_matrix::DenseOctetMatrix;
+use crate::octets::{add_assign, fused_addassign_mul_scalar};
 use crate::rng::rand;
+use crate::symbol_slab::SymbolSlab;
 use crate::systematic_constants::extended_source_block_symbols;
 use crate::systematic_constants::num_hdpc_symbols;
 use crate::systematic_constants::num_intermediate_symbols;
@@ -230,6 +232,64 @@ pub fn generate_constraint_matrix_no_hdpc<T: BinaryMatrix>(
     matrix
 }
 
+// Checks that intermediate_symbols satisfy the LDPC and HDPC relations, as defined in section
+// 5.3.3.3. The solver drops redundant rows, so a solution computed from inconsistent symbols may
+// match every received symbol while violating these.
+#[allow(non_snake_case)]
+pub fn satisfies_precode_relations(
+    source_block_symbols: u32,
+    intermediate_symbols: &SymbolSlab,
+) -> bool {
+    let Kprime = extended_source_block_symbols(source_block_symbols) as usize;
+    let S = num_ldpc_symbols(source_block_symbols) as usize;
+    let H = num_hdpc_symbols(source_block_symbols) as usize;
+    let W = num_lt_symbols(source_block_symbols) as usize;
+    let B = W - S;
+    let P = num_pi_symbols(source_block_symbols) as usize;
+
+    let symbol_size = intermediate_symbols.get(0).len();
+    let mut ldpc = vec![vec![0; symbol_size]; S];
+    // G_LDPC,1
+    for i in 0..B {
+        let a = 1 + i / S;
+        let b = i % S;
+        add_assign(&mut ldpc[b], intermediate_symbols.get(i));
+        let b = (b + a) % S;
+        add_assign(&mut ldpc[b], intermediate_symbols.get(i));
+        let b = (b + a) % S;
+        add_assign(&mut ldpc[b], intermediate_symbols.get(i));
+    }
+    for (i, row) in ldpc.iter_mut().enumerate() {
+        // I_S
+        add_assign(row, intermediate_symbols.get(i + B));
+        // G_LDPC,2
+        add_assign(row, intermediate_symbols.get((i % P) + W));
+        add_assign(row, intermediate_symbols.get(((i + 1) % P) + W));
+    }
+    if ldpc.iter().any(|row| row.iter().any(|byte| *byte != 0)) {
+        return false;
+    }
+
+    // G_HDPC, followed by I_H
+    let hdpc = generate_hdpc_rows(Kprime, S, H);
+    let mut row = vec![0; symbol_size];
+    for i in 0..H {
+        row.copy_from_slice(intermediate_symbols.get(Kprime + S + i));
+        for j in 0..(Kprime + S) {
+            let value = hdpc.get(i, j);
+            if value == Octet::one() {
+                add_assign(&mut row, intermediate_symbols.get(j));
+            } else if value != Octet::zero() {
+                fused_addassign_mul_scalar(&mut row, intermediate_symbols.get(j), &value);
+            }
+        }
+        if row.iter().any(|byte| *byte != 0) {
+            return false;
+        }
+    }
+    true
+}
+
 #[cfg(feature = "std")]
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,522 @@
+use core::fmt;
+use std::io::{self, ErrorKind, Read, Write};
+use std::sync::Arc;
+use std::vec::Vec;
+
+use crate::base::{
+    EncodingPacket, EncodingPacketRef, ObjectTransmissionInformation,
+    ObjectTransmissionInformationError,
+};
+use crate::checksum::{ObjectChecksum, ObjectHasher};
+use crate::decoder::{DecodeError, Decoder};
+use crate::encoder::Encoder;
+use crate::util::crc32;
+
+const CONTAINER_MAGIC: [u8; 4] = *b"RQOC";
+const CONTAINER_VERSION: u8 = 1;
+// Magic, version, OTI, and the checksum algorithm and digest length. The digest and the header
+// checksum follow
+const FIXED_HEADER_BYTES: usize = 4 + 1 + 12 + 2;
+
+/// Reason a container was rejected by `ContainerReader`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ContainerError {
+    /// The data does not start with the container magic bytes.
+    InvalidMagic,
+    /// The container was written with an unsupported format version.
+    UnsupportedVersion(u8),
+    /// The header checksum does not match the header contents.
+    HeaderChecksumMismatch,
+    /// The header contains an invalid object transmission information.
+    InvalidTransmissionInformation(ObjectTransmissionInformationError),
+    /// The decoded object does not match the checksum recorded in the header.
+    ObjectChecksumMismatch,
+    /// The object checksum uses a custom algorithm, but no hasher has been set for it.
+    UnsupportedChecksumAlgorithm(u8),
+}
+
+impl fmt::Display for ContainerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContainerError::InvalidMagic => write!(f, "not a raptorq container"),
+            ContainerError::UnsupportedVersion(version) => {
+                write!(f, "unsupported container version {version}")
+            }
+            ContainerError::HeaderChecksumMismatch => write!(f, "container header is corrupt"),
+            ContainerError::InvalidTransmissionInformation(err) => {
+                write!(f, "invalid object transmission information: {err}")
+            }
+            ContainerError::ObjectChecksumMismatch => {
+                write!(f, "decoded object does not match its checksum")
+            }
+            ContainerError::UnsupportedChecksumAlgorithm(algorithm) => {
+                write!(f, "unsupported checksum algorithm {algorithm}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ContainerError {}
+
+fn invalid_data(err: ContainerError) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, err)
+}
+
+/// Writes encoded packets of an object in the container format, so that they can be stored in a
+/// file and decoded later with `ContainerReader`.
+///
+/// The container starts with a header, followed by any number of packet records:
+///
+/// | Field                           | Size (bytes) |
+/// |---------------------------------|--------------|
+/// | Magic bytes "RQOC"              | 4            |
+/// | Format version, currently 1     | 1            |
+/// | Object Transmission Information | 12           |
+/// | Object checksum algorithm ID    | 1            |
+/// | Object checksum digest length   | 1            |
+/// | Object checksum digest          | variable     |
+/// | CRC-32 of the preceding fields  | 4            |
+///
+/// The object checksum is an `ObjectChecksum`, serialized with `ObjectChecksum::serialize`.
+///
+/// Each record consists of the length of the serialized packet, the CRC-32 of the serialized
+/// packet, and the packet as produced by `EncodingPacket::serialize`. The length and checksums
+/// are big-endian u32s. Every packet carries exactly one symbol, so all records are the same
+/// size, 8 + 4 + symbol size bytes, and the reader relies on this to find the next record. The
+/// length field is only checked, never used to locate records. Records may appear in any order,
+/// and any subset of them is sufficient to decode as long as enough packets of each source block
+/// remain.
+pub struct ContainerWriter<W: Write> {
+    writer: W,
+    config: ObjectTransmissionInformation,
+}
+
+impl<W: Write> ContainerWriter<W> {
+    /// Writes the container header, which records `object_checksum` so that the reader can
+    /// verify the decoded object.
+    pub fn new(
+        mut writer: W,
+        config: ObjectTransmissionInformation,
+        object_checksum: &ObjectChecksum,
+    ) -> io::Result<ContainerWriter<W>> {
+        let mut header = Vec::with_capacity(FIXED_HEADER_BYTES + object_checksum.digest().len());
+        header.extend_from_slice(&CONTAINER_MAGIC);
+        header.push(CONTAINER_VERSION);
+        header.extend_from_slice(&config.serialize());
+        header.extend_from_slice(&object_checksum.serialize());
+        let header_checksum = crc32(&header);
+        header.extend_from_slice(&header_checksum.to_be_bytes());
+        writer.write_all(&header)?;
+        Ok(ContainerWriter { writer, config })
+    }
+
+    pub fn get_config(&self) -> ObjectTransmissionInformation {
+        self.config
+    }
+
+    /// Appends a packet record. Packets whose payload is not exactly one symbol are rejected with
+    /// an error of kind `InvalidInput`, since records must all be the same size.
+    pub fn write_packet(&mut self, packet: &EncodingPacket) -> io::Result<()> {
+        if packet.data().len() != self.config.symbol_size() as usize {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "packet payload of {} bytes does not match the symbol size {}",
+                    packet.data().len(),
+                    self.config.symbol_size()
+                ),
+            ));
+        }
+        let serialized = packet.serialize();
+        self.writer
+            .write_all(&(serialized.len() as u32).to_be_bytes())?;
+        self.writer.write_all(&crc32(&serialized).to_be_bytes())?;
+        self.writer.write_all(&serialized)
+    }
+
+    /// Appends the source packets of every block of `encoder`, each followed by
+    /// `repair_packets_per_block` repair packets.
+    pub fn write_encoder(
+        &mut self,
+        encoder: &Encoder,
+        repair_packets_per_block: u32,
+    ) -> io::Result<()> {
+        assert_eq!(encoder.get_config(), self.config);
+        for packet in encoder.get_encoded_packets(repair_packets_per_block) {
+            self.write_packet(&packet)?;
+        }
+        Ok(())
+    }
+
+    /// Flushes the container, and returns the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Reads a container written by `ContainerWriter`.
+///
+/// Every record of a container is the same size, since all packets carry a full symbol, so a
+/// record whose length or checksum does not match is skipped without losing track of the records
+/// which follow it. Reading stops at a truncated record at the end of the container.
+pub struct ContainerReader<R: Read> {
+    reader: R,
+    config: ObjectTransmissionInformation,
+    object_checksum: ObjectChecksum,
+    object_hasher: Option<Arc<dyn ObjectHasher>>,
+    skipped_records: u64,
+    finished: bool,
+    record: Vec<u8>,
+}
+
+impl<R: Read> ContainerReader<R> {
+    /// Reads and validates the container header. Invalid headers are rejected with an error of
+    /// kind `InvalidData`, wrapping a `ContainerError`.
+    pub fn new(mut reader: R) -> io::Result<ContainerReader<R>> {
+        let mut header = vec![0; FIXED_HEADER_BYTES];
+        reader.read_exact(&mut header)?;
+        if header[..4] != CONTAINER_MAGIC {
+            return Err(invalid_data(ContainerError::InvalidMagic));
+        }
+        let digest_length = header[FIXED_HEADER_BYTES - 1] as usize;
+        header.resize(FIXED_HEADER_BYTES + digest_length + 4, 0);
+        reader.read_exact(&mut header[FIXED_HEADER_BYTES..])?;
+        let (header, header_checksum) = header.split_at(header.len() - 4);
+        if crc32(header) != u32::from_be_bytes(header_checksum.try_into().unwrap()) {
+            return Err(invalid_data(ContainerError::HeaderChecksumMismatch));
+        }
+        if header[4] != CONTAINER_VERSION {
+            return Err(invalid_data(ContainerError::UnsupportedVersion(header[4])));
+        }
+        let config =
+            ObjectTransmissionInformation::try_deserialize(header[5..17].try_into().unwrap())
+                .map_err(|err| invalid_data(ContainerError::InvalidTransmissionInformation(err)))?;
+        // The length of the digest was read from the header itself, so this cannot fail
+        let (object_checksum, _) = ObjectChecksum::deserialize(&header[17..]).unwrap();
+
+        Ok(ContainerReader {
+            reader,
+            config,
+            object_checksum,
+            object_hasher: None,
+            skipped_records: 0,
+            finished: false,
+            record: vec![],
+        })
+    }
+
+    pub fn get_config(&self) -> ObjectTransmissionInformation {
+        self.config
+    }
+
+    /// Checksum of the object, as recorded in the header.
+    pub fn object_checksum(&self) -> &ObjectChecksum {
+        &self.object_checksum
+    }
+
+    /// Sets the hasher used to verify an object checksum created by `ObjectChecksum::with_hasher`.
+    /// See `Decoder::set_object_hasher`.
+    pub fn set_object_hasher(&mut self, hasher: Arc<dyn ObjectHasher>) {
+        self.object_hasher = Some(hasher);
+    }
+
+    /// Number of records which were skipped or discarded because they were corrupt or truncated.
+    pub fn skipped_records(&self) -> u64 {
+        self.skipped_records
+    }
+
+    /// Returns the next intact packet, or `None` once the end of the container, or a record which
+    /// cannot be read past, has been reached.
+    pub fn next_packet(&mut self) -> io::Result<Option<EncodingPacketRef<'_>>> {
+        if !self.read_record()? {
+            return Ok(None);
+        }
+        Ok(Some(EncodingPacketRef::deserialize(&self.record)))
+    }
+
+    // Reads the next intact record into self.record. Returns false at the end of the records.
+    fn read_record(&mut self) -> io::Result<bool> {
+        let record_length = 4 + self.config.symbol_size() as usize;
+        while !self.finished {
+            let mut prefix = [0; 8];
+            let read = read_fully(&mut self.reader, &mut prefix)?;
+            if read < prefix.len() {
+                if read > 0 {
+                    self.skipped_records += 1;
+                }
+                self.finished = true;
+                break;
+            }
+            let length = u32::from_be_bytes(prefix[..4].try_into().unwrap()) as usize;
+            let checksum = u32::from_be_bytes(prefix[4..].try_into().unwrap());
+
+            // Every record has the same length, so a corrupt length field is skipped over like
+            // any other corrupt record
+            self.record.resize(record_length, 0);
+            if read_fully(&mut self.reader, &mut self.record)? < record_length {
+                self.skipped_records += 1;
+                self.finished = true;
+                break;
+            }
+            if length == record_length && crc32(&self.record) == checksum {
+                return Ok(true);
+            }
+            self.skipped_records += 1;
+        }
+        Ok(false)
+    }
+
+    /// Feeds packets to `decoder` until it has decoded the object, and verifies the result
+    /// against the checksum in the header. Returns `None` if the container does not hold enough
+    /// packets to decode the object.
+    ///
+    /// Packets which the decoder rejects are counted as skipped. If the object does not match its
+    /// checksum, an error of kind `InvalidData` wrapping
+    /// `ContainerError::ObjectChecksumMismatch` is returned, or
+    /// `ContainerError::UnsupportedChecksumAlgorithm` if the checksum cannot be computed.
+    pub fn feed(&mut self, decoder: &mut Decoder) -> io::Result<Option<Vec<u8>>> {
+        assert_eq!(decoder.get_config(), self.config);
+        while self.read_record()? {
+            match EncodingPacketRef::try_deserialize(&self.record)
+                .and_then(|packet| decoder.try_decode_ref(packet))
+            {
+                Ok(Some(result)) => {
+                    return match self
+                        .object_checksum
+                        .matches(&result, self.object_hasher.as_deref())
+                    {
+                        Some(true) => Ok(Some(result)),
+                        Some(false) => Err(invalid_data(ContainerError::ObjectChecksumMismatch)),
+                        None => Err(invalid_data(ContainerError::UnsupportedChecksumAlgorithm(
+                            self.object_checksum.algorithm(),
+                        ))),
+                    };
+                }
+                Ok(None) | Err(DecodeError::AlreadyDecoded) => {}
+                Err(_) => self.skipped_records += 1,
+            }
+        }
+        Ok(None)
+    }
+
+    /// Decodes the object from the container. See `feed`.
+    pub fn decode(&mut self) -> io::Result<Option<Vec<u8>>> {
+        self.feed(&mut Decoder::new(self.config))
+    }
+}
+
+// Like read_exact, but returns the number of bytes read if the end of the reader is reached
+fn read_fully<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut read = 0;
+    while read < buf.len() {
+        match reader.read(&mut buf[read..]) {
+            Ok(0) => break,
+            Ok(n) => read += n,
+            Err(err) if err.kind() == ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(read)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use std::io::ErrorKind;
+    use std::sync::Arc;
+    use std::vec::Vec;
+
+    use crate::container::ContainerError;
+    use crate::{
+        ContainerReader, ContainerWriter, Crc32c, Decoder, Encoder, EncodingPacket, ObjectChecksum,
+        ObjectTransmissionInformation,
+    };
+
+    fn gen_test_data(size: usize) -> Vec<u8> {
+        let mut data: Vec<u8> = vec![0; size];
+        for byte in data.iter_mut() {
+            *byte = rand::rng().random();
+        }
+        data
+    }
+
+    fn write_container(data: &[u8], config: ObjectTransmissionInformation) -> Vec<u8> {
+        let encoder = Encoder::new(data, config);
+        let mut writer =
+            ContainerWriter::new(vec![], config, &ObjectChecksum::crc32c(data)).unwrap();
+        writer.write_encoder(&encoder, 5).unwrap();
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let data = gen_test_data(50_000);
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 512, 3, 1, 8);
+        let container = write_container(&data, config);
+
+        let mut reader = ContainerReader::new(&container[..]).unwrap();
+        assert_eq!(reader.get_config(), config);
+        assert_eq!(reader.object_checksum(), &ObjectChecksum::crc32c(&data));
+        let mut packets = vec![];
+        while let Some(packet) = reader.next_packet().unwrap() {
+            packets.push(packet.to_owned_packet());
+        }
+        assert_eq!(packets, Encoder::new(&data, config).get_encoded_packets(5));
+        assert_eq!(reader.skipped_records(), 0);
+
+        let mut reader = ContainerReader::new(&container[..]).unwrap();
+        let mut decoder = Decoder::new(config);
+        assert_eq!(reader.feed(&mut decoder).unwrap().unwrap(), data);
+    }
+
+    #[test]
+    fn corrupt_and_truncated_records() {
+        let data = gen_test_data(50_000);
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 512, 3, 1, 8);
+        let mut container = write_container(&data, config);
+        let header_length = 4 + 1 + 12 + 2 + 4 + 4;
+        let record_length = 8 + 4 + 512;
+
+        // Corrupt the payload of the first two records, and truncate the last one
+        container[header_length + 20] ^= 1;
+        container[header_length + record_length + 20] ^= 1;
+        container.truncate(container.len() - 100);
+
+        let mut reader = ContainerReader::new(&container[..]).unwrap();
+        assert_eq!(reader.decode().unwrap().unwrap(), data);
+
+        let mut reader = ContainerReader::new(&container[..]).unwrap();
+        let mut packets = 0;
+        while reader.next_packet().unwrap().is_some() {
+            packets += 1;
+        }
+        let total = Encoder::new(&data, config).get_encoded_packets(5).len();
+        assert_eq!(packets, total - 3);
+        assert_eq!(reader.skipped_records(), 3);
+    }
+
+    #[test]
+    fn corrupt_record_lengths() {
+        let data = gen_test_data(50_000);
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 512, 3, 1, 8);
+        let mut container = write_container(&data, config);
+        let header_length = 4 + 1 + 12 + 2 + 4 + 4;
+        let record_length = 8 + 4 + 512;
+
+        // Corrupt the length field of the first record, and the checksum field of the third
+        container[header_length] ^= 0x80;
+        container[header_length + 2 * record_length + 4] ^= 1;
+
+        let mut reader = ContainerReader::new(&container[..]).unwrap();
+        let mut packets = 0;
+        while reader.next_packet().unwrap().is_some() {
+            packets += 1;
+        }
+        let total = Encoder::new(&data, config).get_encoded_packets(5).len();
+        assert_eq!(packets, total - 2);
+        assert_eq!(reader.skipped_records(), 2);
+
+        let mut reader = ContainerReader::new(&container[..]).unwrap();
+        assert_eq!(reader.decode().unwrap().unwrap(), data);
+    }
+
+    #[test]
+    fn reject_packets_of_other_sizes() {
+        let data = gen_test_data(10_000);
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 512, 1, 1, 8);
+        let packets = Encoder::new(&data, config).get_encoded_packets(0);
+        let mut writer =
+            ContainerWriter::new(vec![], config, &ObjectChecksum::crc32c(&data)).unwrap();
+        let short = EncodingPacket::new(packets[0].payload_id().clone(), vec![0; 100]);
+        assert_eq!(
+            writer.write_packet(&short).unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+        for packet in packets.iter() {
+            writer.write_packet(packet).unwrap();
+        }
+        let container = writer.finish().unwrap();
+
+        let mut reader = ContainerReader::new(&container[..]).unwrap();
+        assert_eq!(reader.decode().unwrap().unwrap(), data);
+        assert_eq!(reader.skipped_records(), 0);
+    }
+
+    #[test]
+    fn insufficient_packets() {
+        let data = gen_test_data(10_000);
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 512, 1, 1, 8);
+        let mut writer =
+            ContainerWriter::new(vec![], config, &ObjectChecksum::crc32c(&data)).unwrap();
+        writer
+            .write_packet(&Encoder::new(&data, config).get_encoded_packets(0)[0])
+            .unwrap();
+        let container = writer.finish().unwrap();
+        let mut reader = ContainerReader::new(&container[..]).unwrap();
+        assert_eq!(reader.decode().unwrap(), None);
+    }
+
+    #[test]
+    fn invalid_header() {
+        let data = gen_test_data(10_000);
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 512, 1, 1, 8);
+        let container = write_container(&data, config);
+
+        let error_of = |container: &[u8]| {
+            let error = ContainerReader::new(container).err().unwrap();
+            assert_eq!(error.kind(), ErrorKind::InvalidData);
+            error
+                .into_inner()
+                .unwrap()
+                .downcast::<ContainerError>()
+                .map(|err| *err)
+                .unwrap()
+        };
+        let mut corrupt = container.clone();
+        corrupt[0] = b'X';
+        assert_eq!(error_of(&corrupt), ContainerError::InvalidMagic);
+        let mut corrupt = container.clone();
+        corrupt[10] ^= 1;
+        assert_eq!(error_of(&corrupt), ContainerError::HeaderChecksumMismatch);
+
+        // A container whose object checksum is wrong fails verification
+        let error_of_decode = |checksum: &ObjectChecksum| {
+            let mut writer = ContainerWriter::new(vec![], config, checksum).unwrap();
+            writer
+                .write_encoder(&Encoder::new(&data, config), 0)
+                .unwrap();
+            let container = writer.finish().unwrap();
+            let error = ContainerReader::new(&container[..])
+                .unwrap()
+                .decode()
+                .unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::InvalidData);
+            error
+                .into_inner()
+                .unwrap()
+                .downcast::<ContainerError>()
+                .map(|err| *err)
+                .unwrap()
+        };
+        assert_eq!(
+            error_of_decode(&ObjectChecksum::crc32c(&data[1..])),
+            ContainerError::ObjectChecksumMismatch
+        );
+        assert_eq!(
+            error_of_decode(&ObjectChecksum::with_hasher(251, &Crc32c, &data)),
+            ContainerError::UnsupportedChecksumAlgorithm(251)
+        );
+
+        // Custom checksums are verified with the hasher given to the reader
+        let custom = ObjectChecksum::with_hasher(251, &Crc32c, &data);
+        let mut writer = ContainerWriter::new(vec![], config, &custom).unwrap();
+        writer
+            .write_encoder(&Encoder::new(&data, config), 0)
+            .unwrap();
+        let container = writer.finish().unwrap();
+        let mut reader = ContainerReader::new(&container[..]).unwrap();
+        reader.set_object_hasher(Arc::new(Crc32c));
+        assert_eq!(reader.decode().unwrap().unwrap(), data);
+    }
+}
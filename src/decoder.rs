@@ -1,23 +1,34 @@
 #[cfg(feature = "std")]
+use std::sync::Arc;
+#[cfg(feature = "std")]
 use std::{collections::HashSet as Set, iter, vec::Vec};
 
 #[cfg(not(feature = "std"))]
 use core::iter;
 
+use core::fmt;
+
 #[cfg(not(feature = "std"))]
-use alloc::{collections::BTreeSet as Set, vec::Vec};
+use alloc::{collections::BTreeSet as Set, sync::Arc, vec::Vec};
 
 use crate::base::EncodingPacket;
+use crate::base::EncodingPacketRef;
 use crate::base::ObjectTransmissionInformation;
+use crate::base::PayloadId;
 use crate::base::intermediate_tuple;
 use crate::base::partition;
+use crate::checksum::{ObjectChecksum, ObjectHasher, SharedObjectHasher};
 use crate::constraint_matrix::enc_indices;
 use crate::constraint_matrix::generate_constraint_matrix;
 use crate::constraint_matrix::generate_constraint_matrix_no_hdpc;
-use crate::encoder::SPARSE_MATRIX_THRESHOLD;
+use crate::constraint_matrix::satisfies_precode_relations;
+use crate::encoder::{MAX_ENCODING_SYMBOL_ID, SPARSE_MATRIX_THRESHOLD};
 use crate::matrix::{BinaryMatrix, DenseBinaryMatrix};
 use crate::octet_matrix::DenseOctetMatrix;
 use crate::octets::add_assign;
+use crate::operation_vector::{SymbolOps, perform_ops, with_column_threads};
+use crate::packet_auth::SharedPacketVerifier;
+use crate::packet_auth::{PacketVerifier, verify_signed_packet};
 use crate::pi_solver::fused_inverse_mul_symbols;
 use crate::pi_solver::fused_inverse_mul_symbols_no_hdpc;
 use crate::sparse_matrix::SparseBinaryMatrix;
@@ -33,83 +44,350 @@ use crate::util::int_div_ceil;
 #[cfg(feature = "serde_support")]
 use serde::{Deserialize, Serialize};
 
+/// Reason a packet was rejected, or decoding failed, in one of the `try_*` decoding methods.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The source block number does not refer to a block handled by this decoder.
+    InvalidSourceBlockNumber(u8),
+    /// The encoding symbol ID does not fit in 24 bits.
+    InvalidEncodingSymbolId(u32),
+    /// The payload length differs from the symbol size of the decoder.
+    InvalidSymbolLength { expected: usize, actual: usize },
+    /// The source block the packet belongs to has already been decoded.
+    AlreadyDecoded,
+    /// The decoded object does not match its checksum, so at least one received symbol was
+    /// corrupt.
+    IntegrityCheckFailed,
+    /// The object checksum uses a custom algorithm, but no hasher has been set for it.
+    UnsupportedChecksumAlgorithm(u8),
+    /// The packet's authentication trailer is missing or invalid, or the packet was passed to a
+    /// method which cannot verify it while a packet verifier is set.
+    PacketVerificationFailed,
+    /// The packet carries the source symbol with this encoding symbol ID, but the decoder only
+    /// accepts repair symbols.
+    UnexpectedSourceSymbol(u32),
+    /// The serialized packet, of this many bytes, is shorter than its 4 byte payload ID.
+    TruncatedPacket(usize),
+    /// The solver panicked while decoding this source block, which can no longer be recovered.
+    SolverFailed(u8),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidSourceBlockNumber(sbn) => {
+                write!(f, "invalid source block number {sbn}")
+            }
+            DecodeError::InvalidEncodingSymbolId(esi) => {
+                write!(f, "invalid encoding symbol id {esi}")
+            }
+            DecodeError::InvalidSymbolLength { expected, actual } => {
+                write!(f, "expected symbol of {expected} bytes, got {actual} bytes")
+            }
+            DecodeError::AlreadyDecoded => write!(f, "source block has already been decoded"),
+            DecodeError::IntegrityCheckFailed => {
+                write!(f, "decoded object does not match its checksum")
+            }
+            DecodeError::UnsupportedChecksumAlgorithm(algorithm) => {
+                write!(f, "unsupported checksum algorithm {algorithm}")
+            }
+            DecodeError::PacketVerificationFailed => write!(f, "packet failed verification"),
+            DecodeError::UnexpectedSourceSymbol(esi) => {
+                write!(f, "unexpected source symbol {esi} in repair-only stream")
+            }
+            DecodeError::TruncatedPacket(length) => {
+                write!(f, "packet of {length} bytes is shorter than its payload id")
+            }
+            DecodeError::SolverFailed(sbn) => {
+                write!(f, "solver failed while decoding source block {sbn}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+pub(crate) fn create_block_decoders(
+    config: &ObjectTransmissionInformation,
+) -> Vec<SourceBlockDecoder> {
+    let kt = int_div_ceil(config.transfer_length(), config.symbol_size() as u64);
+
+    let (kl, ks, zl, zs) = partition(kt, config.source_blocks());
+
+    let mut decoders = vec![];
+    for i in 0..zl {
+        decoders.push(SourceBlockDecoder::new(
+            i as u8,
+            config,
+            u64::from(kl) * u64::from(config.symbol_size()),
+        ));
+    }
+
+    for i in zl..(zl + zs) {
+        decoders.push(SourceBlockDecoder::new(
+            i as u8,
+            config,
+            u64::from(ks) * u64::from(config.symbol_size()),
+        ));
+    }
+    decoders
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
 pub struct Decoder {
     config: ObjectTransmissionInformation,
     block_decoders: Vec<SourceBlockDecoder>,
     blocks: Vec<Option<Vec<u8>>>,
+    checksum: Option<ObjectChecksum>,
+    #[cfg_attr(feature = "serde_support", serde(skip))]
+    hasher: Option<SharedObjectHasher>,
+    #[cfg_attr(feature = "serde_support", serde(skip))]
+    verifier: Option<SharedPacketVerifier>,
+    repair_only: bool,
 }
 
 impl Decoder {
     pub fn new(config: ObjectTransmissionInformation) -> Decoder {
-        let kt = int_div_ceil(config.transfer_length(), config.symbol_size() as u64);
+        let decoders = create_block_decoders(&config);
+        let blocks = vec![None; decoders.len()];
+        Decoder {
+            config,
+            block_decoders: decoders,
+            blocks,
+            checksum: None,
+            hasher: None,
+            verifier: None,
+            repair_only: false,
+        }
+    }
 
-        let (kl, ks, zl, zs) = partition(kt, config.source_blocks());
+    /// Creates a decoder which verifies the decoded object against `checksum` before returning
+    /// it.
+    ///
+    /// If verification fails, `try_decode` returns `DecodeError::IntegrityCheckFailed`, and
+    /// `decode` returns `None`, rather than returning a corrupt object. Since the corrupt symbol
+    /// cannot be identified, every block is then discarded, and the decoder starts over with the
+    /// packets it receives afterwards.
+    pub fn with_checksum(
+        config: ObjectTransmissionInformation,
+        checksum: ObjectChecksum,
+    ) -> Decoder {
+        let mut decoder = Decoder::new(config);
+        decoder.checksum = Some(checksum);
+        decoder
+    }
 
-        let mut decoders = vec![];
-        for i in 0..zl {
-            decoders.push(SourceBlockDecoder::new(
-                i as u8,
-                &config,
-                u64::from(kl) * u64::from(config.symbol_size()),
-            ));
-        }
+    /// Sets the hasher used to verify a checksum created by `ObjectChecksum::with_hasher`. It must
+    /// be the hasher the sender used for the checksum's algorithm. Checksums using the built-in
+    /// CRC-32C need no hasher.
+    ///
+    /// The hasher is not serialized, and must be set again on a deserialized decoder.
+    pub fn set_object_hasher(&mut self, hasher: Arc<dyn ObjectHasher>) {
+        self.hasher = Some(SharedObjectHasher(hasher));
+    }
 
-        for i in zl..(zl + zs) {
-            decoders.push(SourceBlockDecoder::new(
-                i as u8,
-                &config,
-                u64::from(ks) * u64::from(config.symbol_size()),
-            ));
-        }
+    pub fn get_config(&self) -> ObjectTransmissionInformation {
+        self.config
+    }
 
-        Decoder {
-            config,
-            block_decoders: decoders,
-            blocks: vec![None; (zl + zs) as usize],
+    /// Requires every packet to carry an authentication trailer, which `verifier` checks before
+    /// the packet is accepted. Signed packets are passed to `try_decode_signed`, and the other
+    /// decoding methods ignore or reject packets, since they cannot be verified.
+    ///
+    /// The verifier is not serialized, and must be set again on a deserialized decoder.
+    pub fn set_packet_verifier(&mut self, verifier: Arc<dyn PacketVerifier>) {
+        self.verifier = Some(SharedPacketVerifier(verifier));
+    }
+
+    /// Like `try_decode_ref`, but takes a packet serialized with `EncodingPacket::serialize_signed`,
+    /// and returns `DecodeError::PacketVerificationFailed` unless the verifier accepts its
+    /// trailer. Without a verifier, the whole of `data` is treated as an unsigned packet.
+    pub fn try_decode_signed(&mut self, data: &[u8]) -> Result<Option<Vec<u8>>, DecodeError> {
+        let packet = match self.verifier {
+            Some(ref verifier) => verify_signed_packet(verifier.0.as_ref(), data)
+                .ok_or(DecodeError::PacketVerificationFailed)?,
+            None => EncodingPacketRef::try_deserialize(data)
+                .map_err(|_| DecodeError::PacketVerificationFailed)?,
+        };
+        self.accept_packet_ref(packet)?;
+        self.verified_result()
+    }
+
+    /// Restricts the decoder to streams produced by `Encoder::get_repair_only_packets`. Packets
+    /// carrying a source symbol are rejected with `DecodeError::UnexpectedSourceSymbol` by the
+    /// `try_*` methods, and ignored by the others, so a sender which leaks source data is
+    /// detected rather than silently accepted.
+    pub fn set_repair_only(&mut self, repair_only: bool) {
+        self.repair_only = repair_only;
+    }
+
+    /// Enables detection of corrupted symbols in every source block, excluding up to
+    /// `max_corrupted_symbols` per block. See `SourceBlockDecoder::set_max_corrupted_symbols`.
+    pub fn set_max_corrupted_symbols(&mut self, max_corrupted_symbols: u32) {
+        for block_decoder in self.block_decoders.iter_mut() {
+            block_decoder.set_max_corrupted_symbols(max_corrupted_symbols);
         }
     }
 
-    #[cfg(all(any(test, feature = "benchmarking"), not(feature = "python")))]
+    #[cfg(any(test, feature = "benchmarking"))]
     pub fn set_sparse_threshold(&mut self, value: u32) {
         for block_decoder in self.block_decoders.iter_mut() {
             block_decoder.set_sparse_threshold(value);
         }
     }
 
+    /// Splits each symbol column-wise across up to `threads` threads while decoding a block.
+    /// See `SourceBlockDecoder::set_column_threads`.
+    #[cfg(feature = "parallel")]
+    pub fn set_column_threads(&mut self, threads: usize) {
+        for block_decoder in self.block_decoders.iter_mut() {
+            block_decoder.set_column_threads(threads);
+        }
+    }
+
     pub fn decode(&mut self, packet: EncodingPacket) -> Option<Vec<u8>> {
         let block_number = packet.payload_id.source_block_number() as usize;
-        if self.blocks[block_number].is_none() {
+        if self.blocks[block_number].is_none()
+            && self.verifier.is_none()
+            && !self.is_unexpected_source_symbol(&packet.payload_id)
+        {
             self.blocks[block_number] =
                 self.block_decoders[block_number].decode(iter::once(packet));
         }
-        for block in self.blocks.iter() {
-            if block.is_none() {
-                return None;
-            }
-        }
-
-        let mut result = vec![];
-        for block in self.blocks.iter().flatten() {
-            result.extend(block);
-        }
-
-        result.truncate(self.config.transfer_length() as usize);
-        Some(result)
+        self.verified_result().ok().flatten()
     }
 
-    #[cfg(not(feature = "python"))]
     pub fn add_new_packet(&mut self, packet: EncodingPacket) {
         let block_number = packet.payload_id.source_block_number() as usize;
-        if self.blocks[block_number].is_none() {
+        if self.blocks[block_number].is_none()
+            && self.verifier.is_none()
+            && !self.is_unexpected_source_symbol(&packet.payload_id)
+        {
             self.blocks[block_number] =
                 self.block_decoders[block_number].decode(iter::once(packet));
         }
     }
 
-    #[cfg(not(feature = "python"))]
-    pub fn get_result(&self) -> Option<Vec<u8>> {
+    /// Like `decode`, but takes a borrowed packet. The payload is only copied if the decoder
+    /// needs to retain it.
+    pub fn decode_ref(&mut self, packet: EncodingPacketRef<'_>) -> Option<Vec<u8>> {
+        let block_number = packet.payload_id().source_block_number() as usize;
+        if self.blocks[block_number].is_none()
+            && self.verifier.is_none()
+            && !self.is_unexpected_source_symbol(packet.payload_id())
+        {
+            self.blocks[block_number] =
+                self.block_decoders[block_number].decode_ref(iter::once(packet));
+        }
+        self.verified_result().ok().flatten()
+    }
+
+    /// Like `decode`, but rejects malformed packets instead of panicking.
+    ///
+    /// Returns `DecodeError::AlreadyDecoded` for packets belonging to a source block which has
+    /// already been recovered. Such packets are harmless and may simply be dropped.
+    pub fn try_decode(&mut self, packet: EncodingPacket) -> Result<Option<Vec<u8>>, DecodeError> {
+        self.try_add_new_packet(packet)?;
+        self.verified_result()
+    }
+
+    /// Like `try_decode`, but takes a borrowed packet.
+    pub fn try_decode_ref(
+        &mut self,
+        packet: EncodingPacketRef<'_>,
+    ) -> Result<Option<Vec<u8>>, DecodeError> {
+        if self.verifier.is_some() {
+            return Err(DecodeError::PacketVerificationFailed);
+        }
+        self.accept_packet_ref(packet)?;
+        self.verified_result()
+    }
+
+    /// Like `add_new_packet`, but rejects malformed packets instead of panicking.
+    pub fn try_add_new_packet(&mut self, packet: EncodingPacket) -> Result<(), DecodeError> {
+        if self.verifier.is_some() {
+            return Err(DecodeError::PacketVerificationFailed);
+        }
+        let block_number = self.undecoded_block_number(&packet.payload_id)?;
+        self.blocks[block_number] =
+            self.block_decoders[block_number].try_decode(iter::once(packet))?;
+        Ok(())
+    }
+
+    fn accept_packet_ref(&mut self, packet: EncodingPacketRef<'_>) -> Result<(), DecodeError> {
+        let block_number = self.undecoded_block_number(packet.payload_id())?;
+        self.blocks[block_number] =
+            self.block_decoders[block_number].try_decode_ref(iter::once(packet))?;
+        Ok(())
+    }
+
+    fn undecoded_block_number(&self, payload_id: &PayloadId) -> Result<usize, DecodeError> {
+        let source_block_number = payload_id.source_block_number();
+        let block_number = source_block_number as usize;
+        if block_number >= self.blocks.len() {
+            return Err(DecodeError::InvalidSourceBlockNumber(source_block_number));
+        }
+        if self.blocks[block_number].is_some() {
+            return Err(DecodeError::AlreadyDecoded);
+        }
+        if self.is_unexpected_source_symbol(payload_id) {
+            return Err(DecodeError::UnexpectedSourceSymbol(
+                payload_id.encoding_symbol_id(),
+            ));
+        }
+        Ok(block_number)
+    }
+
+    // The caller must have checked that the source block number is valid
+    fn is_unexpected_source_symbol(&self, payload_id: &PayloadId) -> bool {
+        let block_decoder = &self.block_decoders[payload_id.source_block_number() as usize];
+        self.repair_only && payload_id.encoding_symbol_id() < block_decoder.source_block_symbols()
+    }
+
+    /// Returns the decoders of the individual source blocks, for inspecting decoding progress.
+    pub fn get_block_decoders(&self) -> &Vec<SourceBlockDecoder> {
+        &self.block_decoders
+    }
+
+    // Returns the object once every block has been decoded, if it matches the checksum. If it
+    // does not, the corrupt symbol cannot be identified, so every block is discarded and decoding
+    // starts over with the packets received afterwards
+    fn verified_result(&mut self) -> Result<Option<Vec<u8>>, DecodeError> {
+        let result = self.checked_result();
+        if result == Err(DecodeError::IntegrityCheckFailed) {
+            self.blocks.fill(None);
+            for block_decoder in self.block_decoders.iter_mut() {
+                block_decoder.reset();
+            }
+        }
+        result
+    }
+
+    // Returns the object once every block has been decoded, if it matches the checksum
+    fn checked_result(&self) -> Result<Option<Vec<u8>>, DecodeError> {
+        let Some(result) = self.assemble_result() else {
+            return Ok(None);
+        };
+        if let Some(ref checksum) = self.checksum {
+            match checksum.matches(
+                &result,
+                self.hasher.as_ref().map(|hasher| hasher.0.as_ref()),
+            ) {
+                Some(true) => {}
+                Some(false) => return Err(DecodeError::IntegrityCheckFailed),
+                None => {
+                    return Err(DecodeError::UnsupportedChecksumAlgorithm(
+                        checksum.algorithm(),
+                    ));
+                }
+            }
+        }
+        Ok(Some(result))
+    }
+
+    fn assemble_result(&self) -> Option<Vec<u8>> {
         for block in self.blocks.iter() {
             if block.is_none() {
                 return None;
@@ -123,6 +401,165 @@ impl Decoder {
         result.truncate(self.config.transfer_length() as usize);
         Some(result)
     }
+
+    pub fn get_result(&self) -> Option<Vec<u8>> {
+        self.checked_result().ok().flatten()
+    }
+}
+
+/// Recorded symbol operations which decode a source block from a fixed set of received encoding
+/// symbol IDs.
+///
+/// When the same symbols are repeatedly lost, for example because a storage device is missing,
+/// the plan can be generated once and replayed on new symbol data, which avoids re-running the
+/// solver. See `SourceBlockDecoder::decode_with_plan`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct SourceBlockDecodingPlan {
+    source_symbol_count: u32,
+    // Sorted and deduplicated
+    received_esis: Vec<u32>,
+    hdpc: bool,
+    operations: Vec<SymbolOps>,
+}
+
+impl SourceBlockDecodingPlan {
+    /// Generates a plan for a source block of `source_symbol_count` symbols from which the
+    /// symbols with the given encoding symbol IDs were received. Returns `None` if those symbols
+    /// are insufficient to decode the block.
+    pub fn generate(
+        source_symbol_count: u32,
+        received_esis: &[u32],
+    ) -> Option<SourceBlockDecodingPlan> {
+        assert!(source_symbol_count > 0);
+        let mut received_esis = received_esis.to_vec();
+        received_esis.sort_unstable();
+        received_esis.dedup();
+        assert!(
+            received_esis
+                .iter()
+                .all(|esi| *esi <= MAX_ENCODING_SYMBOL_ID)
+        );
+        if received_esis.len() < source_symbol_count as usize {
+            return None;
+        }
+
+        let num_extended_symbols = extended_source_block_symbols(source_symbol_count);
+        let encoded_isis = extended_isis(source_symbol_count, &received_esis);
+        let s = num_ldpc_symbols(source_symbol_count) as usize;
+        let l = num_intermediate_symbols(source_symbol_count) as usize;
+        let sparse = num_extended_symbols >= SPARSE_MATRIX_THRESHOLD;
+
+        // Same strategy as the decoder: try a pure GF(2) solve first if there are enough rows
+        if s + encoded_isis.len() >= l {
+            let symbols = SymbolSlab::with_zeros(s + encoded_isis.len(), 1);
+            let (_, ops) = if sparse {
+                let matrix = generate_constraint_matrix_no_hdpc::<SparseBinaryMatrix>(
+                    source_symbol_count,
+                    &encoded_isis,
+                );
+                fused_inverse_mul_symbols_no_hdpc(matrix, symbols, source_symbol_count)
+            } else {
+                let matrix = generate_constraint_matrix_no_hdpc::<DenseBinaryMatrix>(
+                    source_symbol_count,
+                    &encoded_isis,
+                );
+                fused_inverse_mul_symbols_no_hdpc(matrix, symbols, source_symbol_count)
+            };
+            if let Some(operations) = ops {
+                return Some(SourceBlockDecodingPlan {
+                    source_symbol_count,
+                    received_esis,
+                    hdpc: false,
+                    operations,
+                });
+            }
+        }
+
+        let h = num_hdpc_symbols(source_symbol_count) as usize;
+        let symbols = SymbolSlab::with_zeros(s + h + encoded_isis.len(), 1);
+        let (_, ops) = if sparse {
+            let (matrix, hdpc) = generate_constraint_matrix::<SparseBinaryMatrix>(
+                source_symbol_count,
+                &encoded_isis,
+            );
+            fused_inverse_mul_symbols(matrix, hdpc, symbols, source_symbol_count)
+        } else {
+            let (matrix, hdpc) =
+                generate_constraint_matrix::<DenseBinaryMatrix>(source_symbol_count, &encoded_isis);
+            fused_inverse_mul_symbols(matrix, hdpc, symbols, source_symbol_count)
+        };
+        Some(SourceBlockDecodingPlan {
+            source_symbol_count,
+            received_esis,
+            hdpc: true,
+            operations: ops?,
+        })
+    }
+
+    /// Number of source symbols, K, the plan was generated for.
+    pub fn source_symbol_count(&self) -> u32 {
+        self.source_symbol_count
+    }
+
+    /// Sorted encoding symbol IDs of the symbols the plan decodes from.
+    pub fn received_esis(&self) -> &[u32] {
+        &self.received_esis
+    }
+}
+
+// Checks that a packet belongs to the source block `source_block_id` and carries a symbol of
+// `symbol_size` bytes
+pub(crate) fn validate_block_packet(
+    source_block_id: u8,
+    symbol_size: u16,
+    payload_id: &PayloadId,
+    payload: &[u8],
+) -> Result<(), DecodeError> {
+    if payload_id.source_block_number() != source_block_id {
+        return Err(DecodeError::InvalidSourceBlockNumber(
+            payload_id.source_block_number(),
+        ));
+    }
+    if payload_id.encoding_symbol_id() > MAX_ENCODING_SYMBOL_ID {
+        return Err(DecodeError::InvalidEncodingSymbolId(
+            payload_id.encoding_symbol_id(),
+        ));
+    }
+    if payload.len() != symbol_size as usize {
+        return Err(DecodeError::InvalidSymbolLength {
+            expected: symbol_size as usize,
+            actual: payload.len(),
+        });
+    }
+    Ok(())
+}
+
+// Converts sorted encoding symbol IDs to internal symbol IDs, inserting the padding symbols after
+// the source symbols. See section 5.3.1
+fn extended_isis(source_symbol_count: u32, sorted_esis: &[u32]) -> Vec<u32> {
+    let num_extended_symbols = extended_source_block_symbols(source_symbol_count);
+    let num_padding_symbols = num_extended_symbols - source_symbol_count;
+    let mut isis: Vec<u32> = sorted_esis
+        .iter()
+        .copied()
+        .take_while(|esi| *esi < source_symbol_count)
+        .collect();
+    isis.extend(source_symbol_count..num_extended_symbols);
+    isis.extend(
+        sorted_esis
+            .iter()
+            .filter(|esi| **esi >= source_symbol_count)
+            .map(|esi| esi + num_padding_symbols),
+    );
+    isis
+}
+
+// Number of source blocks decoded on this thread without the HDPC rows, so that tests can check
+// which solver was used
+#[cfg(all(test, feature = "std"))]
+thread_local! {
+    static NO_HDPC_DECODES: core::cell::Cell<u32> = const { core::cell::Cell::new(0) };
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -139,8 +576,16 @@ pub struct SourceBlockDecoder {
     received_esi: Set<u32>,
     decoded: bool,
     sparse_threshold: u32,
+    column_threads: usize,
+    max_corrupted_symbols: u32,
+    corrupted_esis: Vec<u32>,
+    next_checked_attempt: usize,
 }
 
+// Maximum number of times the block is solved with different symbols excluded, per attempt to
+// identify corrupted symbols
+const MAX_EXCLUSION_SOLVES: usize = 1024;
+
 #[derive(Copy, Clone)]
 struct EncodingParameters {
     lt_symbols: u32,
@@ -168,6 +613,10 @@ impl SourceBlockDecoder {
             received_esi: Set::new(),
             decoded: false,
             sparse_threshold: SPARSE_MATRIX_THRESHOLD,
+            column_threads: 1,
+            max_corrupted_symbols: 0,
+            corrupted_esis: vec![],
+            next_checked_attempt: 0,
         }
     }
 
@@ -176,6 +625,102 @@ impl SourceBlockDecoder {
         self.sparse_threshold = value;
     }
 
+    /// Splits each symbol column-wise across up to `threads` threads while applying the row
+    /// operations of decoding. This only pays off for large symbols, and only when the block is
+    /// not already being decoded in parallel with others, so it is disabled (1) by default.
+    #[cfg(feature = "parallel")]
+    pub fn set_column_threads(&mut self, threads: usize) {
+        self.column_threads = threads;
+    }
+
+    pub fn source_block_id(&self) -> u8 {
+        self.source_block_id
+    }
+
+    /// Number of source symbols, K, in the source block.
+    pub fn source_block_symbols(&self) -> u32 {
+        self.source_block_symbols
+    }
+
+    /// Number of unique source and repair symbols received.
+    pub fn received_symbols(&self) -> u32 {
+        self.received_esi.len() as u32
+    }
+
+    /// Number of unique source symbols received.
+    pub fn received_source_symbols(&self) -> u32 {
+        self.received_source_symbols
+    }
+
+    /// Returns true once the source block has been decoded.
+    pub fn is_decoded(&self) -> bool {
+        self.decoded
+    }
+
+    /// Minimum number of additional unique symbols needed before decoding can succeed.
+    ///
+    /// Once K symbols have been received, decoding may still fail if they are linearly dependent,
+    /// in which case at least one more symbol is needed.
+    pub fn symbols_needed(&self) -> u32 {
+        if self.decoded {
+            return 0;
+        }
+        self.source_block_symbols
+            .saturating_sub(self.received_symbols())
+            .max(1)
+    }
+
+    /// Enables detection of corrupted symbols, using the surplus symbols received beyond K.
+    ///
+    /// Once more than K symbols have been received, the block is solved and every received symbol
+    /// is checked against the solution. If they are inconsistent, the smallest set of at most
+    /// `max_corrupted_symbols` symbols whose exclusion makes the rest consistent is excluded. If
+    /// none is found, decoding waits for more symbols. Identifying n corrupted symbols reliably
+    /// requires at least 2n surplus symbols.
+    ///
+    /// Each attempt solves the block once, and then once for every combination of excluded symbols
+    /// tried, up to 1024 combinations, so an attempt costs at most 1025 solves of the block. The
+    /// combinations containing the symbols inconsistent with the first solution are tried first,
+    /// but for large blocks the budget may not cover every combination of two or more symbols.
+    /// After a failed attempt, the next one is only made once the number of surplus symbols has
+    /// doubled, so receiving n symbols costs at most log2(n) attempts. A value of zero, the
+    /// default, disables detection.
+    pub fn set_max_corrupted_symbols(&mut self, max_corrupted_symbols: u32) {
+        self.max_corrupted_symbols = max_corrupted_symbols;
+    }
+
+    // Discards the received symbols and the decoded block, so that the block is decoded again
+    // from the packets received afterwards
+    pub(crate) fn reset(&mut self) {
+        self.source_symbols.fill(None);
+        self.repair_packets.clear();
+        self.received_source_symbols = 0;
+        self.received_esi = Set::new();
+        self.decoded = false;
+        self.corrupted_esis.clear();
+        self.next_checked_attempt = 0;
+    }
+
+    /// Encoding symbol IDs of the received symbols which were found to be corrupt and excluded
+    /// from decoding, in ascending order.
+    pub fn corrupted_symbols(&self) -> &[u32] {
+        &self.corrupted_esis
+    }
+
+    /// Encoding symbol IDs of the source symbols which have not been received. Empty once the
+    /// block has been decoded.
+    pub fn missing_source_symbols(&self) -> Vec<u32> {
+        if self.decoded {
+            return vec![];
+        }
+        self.source_symbols
+            .iter()
+            .enumerate()
+            .filter(|(_, symbol)| symbol.is_none())
+            .map(|(i, _)| i as u32)
+            .collect()
+    }
+
     fn unpack_sub_blocks(&self, result: &mut [u8], symbol: &[u8], symbol_index: usize) {
         let (tl, ts, nl, ns) = partition(
             (self.symbol_size / self.symbol_alignment as u16) as u32,
@@ -198,6 +743,80 @@ impl SourceBlockDecoder {
         }
     }
 
+    // Returns the (offset within the block, offset within a symbol, length) of each sub-block,
+    // matching the layout used by unpack_sub_blocks()
+    fn sub_block_layout(&self) -> Vec<(usize, usize, usize)> {
+        let (tl, ts, nl, ns) = partition(
+            (self.symbol_size / self.symbol_alignment as u16) as u32,
+            self.num_sub_blocks,
+        );
+
+        let mut layout = vec![];
+        let mut symbol_offset = 0;
+        let mut sub_block_offset = 0;
+        for sub_block in 0..(nl + ns) {
+            let bytes = if sub_block < nl {
+                tl as usize * self.symbol_alignment as usize
+            } else {
+                ts as usize * self.symbol_alignment as usize
+            };
+            layout.push((sub_block_offset, symbol_offset, bytes));
+            symbol_offset += bytes;
+            sub_block_offset += bytes * self.source_block_symbols as usize;
+        }
+        layout
+    }
+
+    /// Byte ranges `[start, end)` of the source block which are available from the source
+    /// symbols received so far, in ascending order. Adjacent ranges are merged.
+    ///
+    /// This allows consuming a partially received block before it can be decoded. Note that
+    /// symbols recovered by decoding are not included.
+    pub fn available_ranges(&self) -> Vec<(usize, usize)> {
+        let mut ranges: Vec<(usize, usize)> = vec![];
+        for (sub_block_offset, _, bytes) in self.sub_block_layout() {
+            for (i, symbol) in self.source_symbols.iter().enumerate() {
+                if symbol.is_none() || bytes == 0 {
+                    continue;
+                }
+                let start = sub_block_offset + bytes * i;
+                match ranges.last_mut() {
+                    Some((_, end)) if *end == start => *end = start + bytes,
+                    _ => ranges.push((start, start + bytes)),
+                }
+            }
+        }
+        ranges
+    }
+
+    /// Copies bytes of the source block, starting at `offset`, into `buf` for as long as they are
+    /// available from received source symbols. Returns the number of bytes copied, which is zero
+    /// if the byte at `offset` has not been received.
+    pub fn read_available(&self, offset: usize, buf: &mut [u8]) -> usize {
+        let layout = self.sub_block_layout();
+        let mut copied = 0;
+        while copied < buf.len() {
+            let position = offset + copied;
+            let Some(&(sub_block_offset, symbol_offset, bytes)) = layout
+                .iter()
+                .rev()
+                .find(|(sub_block_offset, _, bytes)| *sub_block_offset <= position && *bytes > 0)
+            else {
+                break;
+            };
+            let symbol_index = (position - sub_block_offset) / bytes;
+            let within = (position - sub_block_offset) % bytes;
+            let Some(Some(symbol)) = self.source_symbols.get(symbol_index) else {
+                break;
+            };
+            let length = (bytes - within).min(buf.len() - copied);
+            let start = symbol_offset + within;
+            buf[copied..copied + length].copy_from_slice(&symbol.as_bytes()[start..start + length]);
+            copied += length;
+        }
+        copied
+    }
+
     fn try_pi_decode(
         &mut self,
         constraint_matrix: impl BinaryMatrix,
@@ -214,31 +833,7 @@ impl SourceBlockDecoder {
             (Some(s), _) => s,
         };
 
-        let mut result = vec![0; self.symbol_size as usize * self.source_block_symbols as usize];
-        let params = EncodingParameters {
-            lt_symbols: num_lt_symbols(self.source_block_symbols),
-            pi_symbols: num_pi_symbols(self.source_block_symbols),
-            sys_index: systematic_index(self.source_block_symbols),
-            p1: calculate_p1(self.source_block_symbols),
-        };
-        let ss = self.symbol_size as usize;
-        let mut rebuilt_buf = vec![0u8; ss];
-        for i in 0..self.source_block_symbols as usize {
-            if let Some(ref symbol) = self.source_symbols[i] {
-                self.unpack_sub_blocks(&mut result, symbol.as_bytes(), i);
-            } else {
-                self.rebuild_source_symbol_into(
-                    &mut rebuilt_buf,
-                    &intermediate_symbols,
-                    i as u32,
-                    params,
-                );
-                self.unpack_sub_blocks(&mut result, &rebuilt_buf, i);
-            }
-        }
-
-        self.decoded = true;
-        return Some(result);
+        return Some(self.assemble_block(&intermediate_symbols));
     }
 
     /// Attempt to decode without HDPC rows (pure GF(2) solve).
@@ -257,13 +852,16 @@ impl SourceBlockDecoder {
             (Some(s), _) => s,
         };
 
+        #[cfg(all(test, feature = "std"))]
+        NO_HDPC_DECODES.with(|decodes| decodes.set(decodes.get() + 1));
+        Some(self.assemble_block(&intermediate_symbols))
+    }
+
+    // Builds the source block from the received source symbols, rebuilding the missing ones
+    // from the intermediate symbols
+    fn assemble_block(&mut self, intermediate_symbols: &SymbolSlab) -> Vec<u8> {
         let mut result = vec![0; self.symbol_size as usize * self.source_block_symbols as usize];
-        let params = EncodingParameters {
-            lt_symbols: num_lt_symbols(self.source_block_symbols),
-            pi_symbols: num_pi_symbols(self.source_block_symbols),
-            sys_index: systematic_index(self.source_block_symbols),
-            p1: calculate_p1(self.source_block_symbols),
-        };
+        let params = self.encoding_parameters();
         let mut rebuilt_buf = vec![0u8; self.symbol_size as usize];
         for i in 0..self.source_block_symbols as usize {
             if let Some(ref symbol) = self.source_symbols[i] {
@@ -271,7 +869,7 @@ impl SourceBlockDecoder {
             } else {
                 self.rebuild_source_symbol_into(
                     &mut rebuilt_buf,
-                    &intermediate_symbols,
+                    intermediate_symbols,
                     i as u32,
                     params,
                 );
@@ -279,35 +877,205 @@ impl SourceBlockDecoder {
             }
         }
 
-        self.decoded = true;
-        Some(result)
+        self.decoded = true;
+        result
+    }
+
+    pub fn decode<T: IntoIterator<Item = EncodingPacket>>(
+        &mut self,
+        packets: T,
+    ) -> Option<Vec<u8>> {
+        for packet in packets {
+            assert_eq!(
+                self.source_block_id,
+                packet.payload_id.source_block_number()
+            );
+
+            let (payload_id, payload) = packet.split();
+            self.add_symbol(payload_id, payload);
+        }
+
+        self.try_complete()
+    }
+
+    /// Like `decode`, but takes borrowed packets. Payloads are only copied if the decoder needs
+    /// to retain them, so duplicate packets are never copied.
+    pub fn decode_ref<'a, T: IntoIterator<Item = EncodingPacketRef<'a>>>(
+        &mut self,
+        packets: T,
+    ) -> Option<Vec<u8>> {
+        for packet in packets {
+            assert_eq!(
+                self.source_block_id,
+                packet.payload_id().source_block_number()
+            );
+
+            self.add_symbol(packet.payload_id().clone(), packet.data());
+        }
+
+        self.try_complete()
+    }
+
+    /// Like `decode`, but rejects malformed packets instead of panicking.
+    ///
+    /// Packets preceding the first invalid one are retained by the decoder.
+    pub fn try_decode<T: IntoIterator<Item = EncodingPacket>>(
+        &mut self,
+        packets: T,
+    ) -> Result<Option<Vec<u8>>, DecodeError> {
+        if self.decoded {
+            return Err(DecodeError::AlreadyDecoded);
+        }
+        for packet in packets {
+            self.validate_packet(packet.payload_id(), packet.data())?;
+            let (payload_id, payload) = packet.split();
+            self.add_symbol(payload_id, payload);
+        }
+
+        Ok(self.try_complete())
+    }
+
+    /// Like `try_decode`, but takes borrowed packets.
+    pub fn try_decode_ref<'a, T: IntoIterator<Item = EncodingPacketRef<'a>>>(
+        &mut self,
+        packets: T,
+    ) -> Result<Option<Vec<u8>>, DecodeError> {
+        if self.decoded {
+            return Err(DecodeError::AlreadyDecoded);
+        }
+        for packet in packets {
+            self.validate_packet(packet.payload_id(), packet.data())?;
+            self.add_symbol(packet.payload_id().clone(), packet.data());
+        }
+
+        Ok(self.try_complete())
+    }
+
+    /// Like `decode`, but replays `plan` instead of running the solver if every symbol the plan
+    /// decodes from has been received. Otherwise, decoding proceeds as in `decode`.
+    pub fn decode_with_plan<T: IntoIterator<Item = EncodingPacket>>(
+        &mut self,
+        packets: T,
+        plan: &SourceBlockDecodingPlan,
+    ) -> Option<Vec<u8>> {
+        for packet in packets {
+            assert_eq!(
+                self.source_block_id,
+                packet.payload_id.source_block_number()
+            );
+
+            let (payload_id, payload) = packet.split();
+            self.add_symbol(payload_id, payload);
+        }
+
+        if self.received_source_symbols == self.source_block_symbols
+            || plan.source_symbol_count != self.source_block_symbols
+            || !plan
+                .received_esis
+                .iter()
+                .all(|esi| self.received_esi.contains(esi))
+        {
+            return self.try_complete();
+        }
+
+        let mut zero_rows = num_ldpc_symbols(self.source_block_symbols) as usize;
+        if plan.hdpc {
+            zero_rows += num_hdpc_symbols(self.source_block_symbols) as usize;
+        }
+        let mut d = self.symbol_rows(zero_rows, &plan.received_esis);
+        with_column_threads(self.column_threads, || {
+            perform_ops(&plan.operations, &mut d)
+        });
+        Some(self.assemble_block(&d))
+    }
+
+    // Builds the D vector of section 5.3.3.4.2 from `zero_rows` zero symbols, followed by the
+    // received symbols with the given sorted encoding symbol IDs, with the padding symbols
+    // inserted after the source symbols
+    fn symbol_rows(&self, zero_rows: usize, sorted_esis: &[u32]) -> SymbolSlab {
+        let mut repair_packets: Vec<(u32, &[u8])> = self
+            .repair_packets
+            .iter()
+            .map(|packet| (packet.payload_id.encoding_symbol_id(), packet.data()))
+            .collect();
+        repair_packets.sort_unstable_by_key(|(esi, _)| *esi);
+
+        let num_extended_symbols = extended_source_block_symbols(self.source_block_symbols);
+        let num_padding = (num_extended_symbols - self.source_block_symbols) as usize;
+        let mut d = SymbolSlab::with_zeros(
+            zero_rows + sorted_esis.len() + num_padding,
+            self.symbol_size as usize,
+        );
+        let mut row = zero_rows;
+        let mut padded = false;
+        for &esi in sorted_esis.iter() {
+            if esi >= self.source_block_symbols && !padded {
+                // Padding rows already zero
+                row += num_padding;
+                padded = true;
+            }
+            d.get_mut(row)
+                .copy_from_slice(self.received_symbol(esi, &repair_packets));
+            row += 1;
+        }
+        d
+    }
+
+    fn received_symbol<'a>(
+        &'a self,
+        esi: u32,
+        sorted_repair_packets: &[(u32, &'a [u8])],
+    ) -> &'a [u8] {
+        if esi < self.source_block_symbols {
+            self.source_symbols[esi as usize]
+                .as_ref()
+                .unwrap()
+                .as_bytes()
+        } else {
+            let index = sorted_repair_packets
+                .binary_search_by_key(&esi, |(esi, _)| *esi)
+                .unwrap();
+            sorted_repair_packets[index].1
+        }
     }
 
-    pub fn decode<T: IntoIterator<Item = EncodingPacket>>(
-        &mut self,
-        packets: T,
-    ) -> Option<Vec<u8>> {
-        for packet in packets {
-            assert_eq!(
-                self.source_block_id,
-                packet.payload_id.source_block_number()
-            );
+    /// Sorted encoding symbol IDs of the symbols received so far. Along with the number of source
+    /// symbols, this identifies the `SourceBlockDecodingPlan` which decodes the block.
+    pub fn received_esis(&self) -> Vec<u32> {
+        let mut esis: Vec<u32> = self.received_esi.iter().copied().collect();
+        esis.sort_unstable();
+        esis
+    }
 
-            let (payload_id, payload) = packet.split();
-            if self.received_esi.insert(payload_id.encoding_symbol_id()) {
-                if payload_id.encoding_symbol_id() >= self.source_block_symbols {
-                    // Repair symbol
-                    self.repair_packets
-                        .push(EncodingPacket::new(payload_id, payload));
-                } else {
-                    // Source symbol
-                    self.source_symbols[payload_id.encoding_symbol_id() as usize] =
-                        Some(Symbol::new(payload));
-                    self.received_source_symbols += 1;
-                }
+    pub(crate) fn validate_packet(
+        &self,
+        payload_id: &PayloadId,
+        payload: &[u8],
+    ) -> Result<(), DecodeError> {
+        validate_block_packet(self.source_block_id, self.symbol_size, payload_id, payload)
+    }
+
+    // Takes ownership of the payload only if the symbol has not been received before
+    pub(crate) fn add_symbol<P: Into<Vec<u8>>>(&mut self, payload_id: PayloadId, payload: P) {
+        if self.received_esi.insert(payload_id.encoding_symbol_id()) {
+            if payload_id.encoding_symbol_id() >= self.source_block_symbols {
+                // Repair symbol
+                self.repair_packets
+                    .push(EncodingPacket::new(payload_id, payload.into()));
+            } else {
+                // Source symbol
+                self.source_symbols[payload_id.encoding_symbol_id() as usize] =
+                    Some(Symbol::new(payload.into()));
+                self.received_source_symbols += 1;
             }
         }
+    }
+
+    pub(crate) fn try_complete(&mut self) -> Option<Vec<u8>> {
+        with_column_threads(self.column_threads, || self.solve())
+    }
 
+    fn solve(&mut self) -> Option<Vec<u8>> {
         let num_extended_symbols = extended_source_block_symbols(self.source_block_symbols);
         let num_padding_symbols = num_extended_symbols - self.source_block_symbols;
 
@@ -316,6 +1084,10 @@ impl SourceBlockDecoder {
             return None;
         }
 
+        if self.max_corrupted_symbols > 0 {
+            return self.try_complete_checked();
+        }
+
         // Case 2: we have all source symbols and can return them without decoding
         if self.received_source_symbols == self.source_block_symbols {
             let mut result =
@@ -425,6 +1197,159 @@ impl SourceBlockDecoder {
         }
     }
 
+    fn encoding_parameters(&self) -> EncodingParameters {
+        EncodingParameters {
+            lt_symbols: num_lt_symbols(self.source_block_symbols),
+            pi_symbols: num_pi_symbols(self.source_block_symbols),
+            sys_index: systematic_index(self.source_block_symbols),
+            p1: calculate_p1(self.source_block_symbols),
+        }
+    }
+
+    // Decodes like try_complete(), but first excludes received symbols which are inconsistent
+    // with the others. See set_max_corrupted_symbols()
+    fn try_complete_checked(&mut self) -> Option<Vec<u8>> {
+        let k = self.source_block_symbols as usize;
+        let esis = self.received_esis();
+        // At least one surplus symbol is needed to detect an inconsistency
+        if esis.len() <= k || esis.len() < self.next_checked_attempt {
+            return None;
+        }
+
+        let result = self.exclude_corrupted_symbols(&esis);
+        if result.is_none() {
+            // Wait until the surplus has doubled before searching again, so that a block which
+            // keeps failing costs a logarithmic rather than linear number of searches
+            self.next_checked_attempt = esis.len() + (esis.len() - k);
+        }
+        result
+    }
+
+    fn exclude_corrupted_symbols(&mut self, esis: &[u32]) -> Option<Vec<u8>> {
+        let k = self.source_block_symbols as usize;
+        let intermediate_symbols = self.solve_with(esis)?;
+        let inconsistent = self.inconsistent_symbols(&intermediate_symbols, esis);
+        let (excluded, intermediate_symbols) = if inconsistent.is_empty()
+            && satisfies_precode_relations(self.source_block_symbols, &intermediate_symbols)
+        {
+            (vec![], intermediate_symbols)
+        } else {
+            // The inconsistent symbols are the most likely culprits, so they are tried first
+            let mut candidates = inconsistent.clone();
+            candidates.extend(esis.iter().filter(|esi| !inconsistent.contains(esi)));
+            let mut budget = MAX_EXCLUSION_SOLVES;
+            (1..=self.max_corrupted_symbols as usize)
+                .take_while(|count| esis.len() - count > k)
+                .find_map(|count| {
+                    self.exclude_combinations(esis, &candidates, count, &mut budget)
+                })?
+        };
+
+        // Forget the corrupted symbols, so that missing source symbols are rebuilt
+        for &esi in excluded.iter() {
+            if esi < self.source_block_symbols {
+                self.source_symbols[esi as usize] = None;
+                self.received_source_symbols -= 1;
+            } else {
+                self.repair_packets
+                    .retain(|packet| packet.payload_id.encoding_symbol_id() != esi);
+            }
+        }
+        self.corrupted_esis.extend(excluded);
+        self.corrupted_esis.sort_unstable();
+        Some(self.assemble_block(&intermediate_symbols))
+    }
+
+    // Searches the combinations of `count` candidates, in order, for one whose exclusion leaves
+    // a consistent set of symbols, solving the block at most `budget` times. Returns the excluded
+    // symbols and the resulting solution.
+    fn exclude_combinations(
+        &self,
+        sorted_esis: &[u32],
+        candidates: &[u32],
+        count: usize,
+        budget: &mut usize,
+    ) -> Option<(Vec<u32>, SymbolSlab)> {
+        let n = candidates.len();
+        let mut indices: Vec<usize> = (0..count).collect();
+        loop {
+            *budget = budget.checked_sub(1)?;
+            let mut excluded: Vec<u32> = indices.iter().map(|i| candidates[*i]).collect();
+            let remaining: Vec<u32> = sorted_esis
+                .iter()
+                .copied()
+                .filter(|esi| !excluded.contains(esi))
+                .collect();
+            if let Some(solution) = self.solve_with(&remaining)
+                && self.inconsistent_symbols(&solution, &remaining).is_empty()
+                && satisfies_precode_relations(self.source_block_symbols, &solution)
+            {
+                excluded.sort_unstable();
+                return Some((excluded, solution));
+            }
+
+            let i = (0..count).rev().find(|&i| indices[i] < n - count + i)?;
+            indices[i] += 1;
+            for j in (i + 1)..count {
+                indices[j] = indices[j - 1] + 1;
+            }
+        }
+    }
+
+    // Solves for the intermediate symbols using only the received symbols in sorted_esis
+    fn solve_with(&self, sorted_esis: &[u32]) -> Option<SymbolSlab> {
+        let s = num_ldpc_symbols(self.source_block_symbols) as usize;
+        let h = num_hdpc_symbols(self.source_block_symbols) as usize;
+        let d = self.symbol_rows(s + h, sorted_esis);
+        let encoded_isis = extended_isis(self.source_block_symbols, sorted_esis);
+        if extended_source_block_symbols(self.source_block_symbols) >= self.sparse_threshold {
+            let (constraint_matrix, hdpc) = generate_constraint_matrix::<SparseBinaryMatrix>(
+                self.source_block_symbols,
+                &encoded_isis,
+            );
+            fused_inverse_mul_symbols(constraint_matrix, hdpc, d, self.source_block_symbols).0
+        } else {
+            let (constraint_matrix, hdpc) = generate_constraint_matrix::<DenseBinaryMatrix>(
+                self.source_block_symbols,
+                &encoded_isis,
+            );
+            fused_inverse_mul_symbols(constraint_matrix, hdpc, d, self.source_block_symbols).0
+        }
+    }
+
+    // Returns the encoding symbol IDs of the received symbols in sorted_esis which differ from
+    // the symbols generated from intermediate_symbols
+    fn inconsistent_symbols(
+        &self,
+        intermediate_symbols: &SymbolSlab,
+        sorted_esis: &[u32],
+    ) -> Vec<u32> {
+        let mut repair_packets: Vec<(u32, &[u8])> = self
+            .repair_packets
+            .iter()
+            .map(|packet| (packet.payload_id.encoding_symbol_id(), packet.data()))
+            .collect();
+        repair_packets.sort_unstable_by_key(|(esi, _)| *esi);
+
+        let num_padding =
+            extended_source_block_symbols(self.source_block_symbols) - self.source_block_symbols;
+        let params = self.encoding_parameters();
+        let mut generated = vec![0; self.symbol_size as usize];
+        let mut inconsistent = vec![];
+        for &esi in sorted_esis {
+            let isi = if esi < self.source_block_symbols {
+                esi
+            } else {
+                esi + num_padding
+            };
+            self.rebuild_source_symbol_into(&mut generated, intermediate_symbols, isi, params);
+            if generated != self.received_symbol(esi, &repair_packets) {
+                inconsistent.push(esi);
+            }
+        }
+        inconsistent
+    }
+
     fn rebuild_source_symbol_into(
         &self,
         dest: &mut [u8],
@@ -468,32 +1393,27 @@ mod codec_tests {
     };
 
     use rand::Rng;
-    #[cfg(not(feature = "python"))]
     use rand::seq::SliceRandom;
 
-    #[cfg(not(feature = "python"))]
     use crate::Decoder;
+    use crate::decoder::NO_HDPC_DECODES;
     use crate::systematic_constants::{num_intermediate_symbols, num_ldpc_symbols};
-    #[cfg(not(feature = "python"))]
-    use crate::{Encoder, EncoderBuilder};
+    use crate::{Crc32c, Encoder, EncoderBuilder, ObjectChecksum, PacketSigner, PacketVerifier};
     use crate::{
-        ObjectTransmissionInformation, SourceBlockDecoder, SourceBlockEncoder,
-        SourceBlockEncodingPlan,
+        DecodeError, EncodingPacket, EncodingPacketRef, ObjectTransmissionInformation, PayloadId,
+        SourceBlockDecoder, SourceBlockDecodingPlan, SourceBlockEncoder, SourceBlockEncodingPlan,
     };
 
-    #[cfg(not(feature = "python"))]
     #[test]
     fn random_erasure_dense() {
         random_erasure(99_999);
     }
 
-    #[cfg(not(feature = "python"))]
     #[test]
     fn random_erasure_sparse() {
         random_erasure(0);
     }
 
-    #[cfg(not(feature = "python"))]
     fn random_erasure(sparse_threshold: u32) {
         let elements: usize = rand::rng().random_range(1..1_000_000);
         let mut data: Vec<u8> = vec![0; elements];
@@ -526,7 +1446,6 @@ mod codec_tests {
         assert_eq!(result.unwrap(), data);
     }
 
-    #[cfg(not(feature = "python"))]
     #[test]
     fn sub_block_erasure() {
         let elements: usize = 10_000;
@@ -570,6 +1489,541 @@ mod codec_tests {
         assert_eq!(result.unwrap(), data);
     }
 
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn column_threads_round_trip() {
+        let data: Vec<u8> = (0..40 * 16_384).map(|_| rand::rng().random()).collect();
+        let mut builder = EncoderBuilder::new();
+        builder.set_max_packet_size(16_384);
+        builder.set_decoder_memory_requirement(u64::MAX);
+        builder.set_column_threads(4);
+        let encoder = builder.build(&data);
+        assert_eq!(encoder.get_config().symbol_size(), 16_384);
+
+        let mut packets = encoder.get_encoded_packets(10);
+        packets.shuffle(&mut rand::rng());
+        packets.truncate(packets.len() - 10);
+
+        let mut decoder = Decoder::new(encoder.get_config());
+        decoder.set_column_threads(4);
+        let mut result = None;
+        for packet in packets {
+            result = decoder.decode(packet);
+            if result.is_some() {
+                break;
+            }
+        }
+        assert_eq!(result.unwrap(), data);
+    }
+
+    #[test]
+    fn verify_object_checksum() {
+        let data: Vec<u8> = (0..10_000).map(|_| rand::rng().random()).collect();
+        let encoder = Encoder::with_defaults(&data, 512);
+        let checksum = ObjectChecksum::crc32c(&data);
+
+        let mut decoder = Decoder::with_checksum(encoder.get_config(), checksum.clone());
+        let mut result = None;
+        for packet in encoder.get_encoded_packets(0) {
+            result = decoder.try_decode(packet).unwrap();
+        }
+        assert_eq!(result.unwrap(), data);
+
+        // Flip a bit in one symbol, which silently corrupts the output without a checksum
+        let mut packets = encoder.get_encoded_packets(0);
+        let (payload_id, mut payload) = packets.remove(3).split();
+        payload[7] ^= 1;
+        packets.insert(3, EncodingPacket::new(payload_id, payload));
+
+        let mut decoder = Decoder::new(encoder.get_config());
+        let mut checked_decoder = Decoder::with_checksum(encoder.get_config(), checksum);
+        let last = packets.pop().unwrap();
+        for packet in packets {
+            assert_eq!(decoder.decode(packet.clone()), None);
+            assert_eq!(checked_decoder.try_decode(packet), Ok(None));
+        }
+        assert_ne!(decoder.decode(last.clone()).unwrap(), data);
+        assert_eq!(
+            checked_decoder.try_decode(last),
+            Err(DecodeError::IntegrityCheckFailed)
+        );
+        assert_eq!(checked_decoder.get_result(), None);
+
+        // The decoder starts over, and recovers from intact packets
+        let mut result = Ok(None);
+        for packet in encoder.get_encoded_packets(0) {
+            result = checked_decoder.try_decode(packet);
+        }
+        assert_eq!(result.unwrap().unwrap(), data);
+
+        let custom = ObjectChecksum::with_hasher(250, &Crc32c, &data);
+        let mut decoder = Decoder::with_checksum(encoder.get_config(), custom.clone());
+        let mut result = Ok(None);
+        for packet in encoder.get_encoded_packets(0) {
+            result = decoder.try_decode(packet);
+        }
+        assert_eq!(result, Err(DecodeError::UnsupportedChecksumAlgorithm(250)));
+
+        let mut decoder = Decoder::with_checksum(encoder.get_config(), custom);
+        decoder.set_object_hasher(Arc::new(Crc32c));
+        let mut result = Ok(None);
+        for packet in encoder.get_encoded_packets(0) {
+            result = decoder.try_decode(packet);
+        }
+        assert_eq!(result.unwrap().unwrap(), data);
+    }
+
+    fn corrupt(packet: EncodingPacket, byte: usize) -> EncodingPacket {
+        let (payload_id, mut payload) = packet.split();
+        payload[byte] ^= 0x5A;
+        EncodingPacket::new(payload_id, payload)
+    }
+
+    #[test]
+    fn exclude_corrupted_symbols() {
+        let symbol_size = 8;
+        let symbols = 40;
+        let data: Vec<u8> = (0..symbol_size * symbols)
+            .map(|_| rand::rng().random())
+            .collect();
+        let config = ObjectTransmissionInformation::new(0, symbol_size as u16, 1, 1, 1);
+        let encoder = SourceBlockEncoder::new(1, &config, &data);
+        let mut packets = encoder.source_packets();
+        packets.extend(encoder.repair_packets(0, 4));
+        // Corrupt one source symbol, and one repair symbol
+        packets[5] = corrupt(packets[5].clone(), 3);
+        packets[symbols + 2] = corrupt(packets[symbols + 2].clone(), 0);
+
+        let mut decoder = SourceBlockDecoder::new(1, &config, data.len() as u64);
+        decoder.set_max_corrupted_symbols(2);
+        assert_eq!(decoder.decode(packets.clone()), Some(data.clone()));
+        assert_eq!(decoder.corrupted_symbols(), &[5, symbols as u32 + 2]);
+
+        // Without enough surplus symbols to identify them, decoding waits for more
+        let mut decoder = SourceBlockDecoder::new(1, &config, data.len() as u64);
+        decoder.set_max_corrupted_symbols(2);
+        packets.truncate(symbols + 1);
+        assert_eq!(decoder.decode(packets.clone()), None);
+
+        // Without detection, the corrupt source symbol is returned as is
+        let mut decoder = SourceBlockDecoder::new(1, &config, data.len() as u64);
+        assert_ne!(decoder.decode(packets), Some(data));
+    }
+
+    #[test]
+    fn corrupted_symbol_search_backs_off() {
+        let symbol_size = 8;
+        let symbols = 40;
+        let data: Vec<u8> = (0..symbol_size * symbols)
+            .map(|_| rand::rng().random())
+            .collect();
+        let config = ObjectTransmissionInformation::new(0, symbol_size as u16, 1, 1, 1);
+        let encoder = SourceBlockEncoder::new(1, &config, &data);
+        let mut packets = encoder.source_packets();
+        packets.extend(encoder.repair_packets(0, 4));
+        packets[5] = corrupt(packets[5].clone(), 3);
+        packets[9] = corrupt(packets[9].clone(), 1);
+
+        let mut decoder = SourceBlockDecoder::new(1, &config, data.len() as u64);
+        decoder.set_max_corrupted_symbols(2);
+        // Two surplus symbols cannot identify two corrupted symbols
+        assert_eq!(decoder.decode(packets[..symbols + 2].iter().cloned()), None);
+        // So the search is only repeated once the surplus has doubled
+        assert_eq!(decoder.next_checked_attempt, symbols + 4);
+        assert_eq!(
+            decoder.decode(iter::once(packets[symbols + 2].clone())),
+            None
+        );
+        assert_eq!(
+            decoder.decode(iter::once(packets[symbols + 3].clone())),
+            Some(data)
+        );
+        assert_eq!(decoder.corrupted_symbols(), &[5, 9]);
+    }
+
+    // Keyed checksum, standing in for an HMAC
+    struct KeyedSum(u8);
+
+    impl PacketSigner for KeyedSum {
+        fn sign(&self, packet: &[u8]) -> Vec<u8> {
+            vec![
+                packet
+                    .iter()
+                    .fold(self.0, |acc, byte| acc.wrapping_add(*byte)),
+            ]
+        }
+    }
+
+    impl PacketVerifier for KeyedSum {
+        fn trailer_len(&self) -> usize {
+            1
+        }
+
+        fn verify(&self, packet: &[u8], trailer: &[u8]) -> bool {
+            self.sign(packet) == trailer
+        }
+    }
+
+    #[test]
+    fn verify_signed_packets() {
+        let data: Vec<u8> = (0..10_000).map(|_| rand::rng().random()).collect();
+        let encoder = Encoder::with_defaults(&data, 500);
+        let packets = encoder.get_encoded_packets(0);
+        let mut decoder = Decoder::new(encoder.get_config());
+        decoder.set_packet_verifier(Arc::new(KeyedSum(7)));
+
+        // Forged by a sender without the key
+        let forged = packets[0].serialize_signed(&KeyedSum(8));
+        assert_eq!(
+            decoder.try_decode_signed(&forged),
+            Err(DecodeError::PacketVerificationFailed)
+        );
+        assert_eq!(
+            decoder.try_decode_signed(&[0; 4]),
+            Err(DecodeError::PacketVerificationFailed)
+        );
+        assert_eq!(
+            decoder.try_decode(packets[0].clone()),
+            Err(DecodeError::PacketVerificationFailed)
+        );
+        assert_eq!(decoder.decode(packets[0].clone()), None);
+        assert_eq!(decoder.get_block_decoders()[0].received_source_symbols(), 0);
+
+        let mut result = None;
+        for packet in packets.iter() {
+            result = decoder
+                .try_decode_signed(&packet.serialize_signed(&KeyedSum(7)))
+                .unwrap();
+        }
+        assert_eq!(result.unwrap(), data);
+    }
+
+    #[test]
+    fn try_decode_rejects_malformed_packets() {
+        let data: Vec<u8> = (0..10_000).map(|_| rand::rng().random()).collect();
+        let encoder = Encoder::with_defaults(&data, 500);
+        let config = encoder.get_config();
+        let mut decoder = Decoder::new(config);
+
+        let bad_block = EncodingPacket::new(
+            PayloadId::new(config.source_blocks(), 0),
+            vec![0; config.symbol_size() as usize],
+        );
+        assert_eq!(
+            decoder.try_decode(bad_block),
+            Err(DecodeError::InvalidSourceBlockNumber(
+                config.source_blocks()
+            ))
+        );
+
+        let short_payload = EncodingPacket::new(PayloadId::new(0, 0), vec![0; 3]);
+        assert_eq!(
+            decoder.try_decode(short_payload),
+            Err(DecodeError::InvalidSymbolLength {
+                expected: config.symbol_size() as usize,
+                actual: 3
+            })
+        );
+
+        let mut result = None;
+        for packet in encoder.get_encoded_packets(0) {
+            assert_eq!(result, None);
+            result = decoder.try_decode(packet).unwrap();
+        }
+        assert_eq!(result.unwrap(), data);
+
+        let late_packet = encoder.get_encoded_packets(1).pop().unwrap();
+        assert_eq!(
+            decoder.try_decode(late_packet),
+            Err(DecodeError::AlreadyDecoded)
+        );
+    }
+
+    #[test]
+    fn repair_only_round_trip() {
+        let data: Vec<u8> = (0..19_000).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 64, 3, 1, 8);
+        let encoder = Encoder::new(&data, config);
+        let source_symbols: Vec<u32> = encoder
+            .get_block_encoders()
+            .iter()
+            .map(|block| block.source_packets().len() as u32)
+            .collect();
+
+        let mut packets = encoder.get_repair_only_packets(2);
+        assert_eq!(
+            packets.len() as u32,
+            source_symbols.iter().sum::<u32>() + 2 * source_symbols.len() as u32
+        );
+        for packet in packets.iter() {
+            let block = packet.payload_id.source_block_number() as usize;
+            assert!(packet.payload_id.encoding_symbol_id() >= source_symbols[block]);
+        }
+        packets.shuffle(&mut rand::rng());
+
+        let mut decoder = Decoder::new(config);
+        decoder.set_repair_only(true);
+        let source_packet = encoder.get_encoded_packets(0).remove(0);
+        assert_eq!(
+            decoder.try_decode(source_packet.clone()),
+            Err(DecodeError::UnexpectedSourceSymbol(0))
+        );
+        assert_eq!(decoder.decode(source_packet), None);
+        assert_eq!(decoder.get_block_decoders()[0].received_symbols(), 0);
+
+        let mut result = None;
+        for packet in packets {
+            match decoder.try_decode(packet) {
+                Ok(Some(decoded)) => result = Some(decoded),
+                Ok(None) | Err(DecodeError::AlreadyDecoded) => {}
+                Err(err) => panic!("{err}"),
+            }
+        }
+        assert_eq!(result.unwrap(), data);
+
+        let mut decoder = Decoder::new(config);
+        decoder.set_repair_only(true);
+        let mut result = None;
+        for packet in encoder.repair_only_packet_iter() {
+            let block = packet.payload_id.source_block_number() as usize;
+            assert!(packet.payload_id.encoding_symbol_id() >= source_symbols[block]);
+            result = decoder.decode(packet);
+            if result.is_some() {
+                break;
+            }
+        }
+        assert_eq!(result.unwrap(), data);
+    }
+
+    #[test]
+    fn repair_only_no_hdpc() {
+        let data: Vec<u8> = (0..8_000).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 64, 2, 1, 8);
+        let encoder = Encoder::new(&data, config);
+
+        // With only repair symbols, the pure GF(2) solve needs at least K + H of them
+        let overhead = encoder
+            .get_block_encoders()
+            .iter()
+            .map(|block| {
+                let k = block.source_packets().len() as u32;
+                num_intermediate_symbols(k) - num_ldpc_symbols(k) - k
+            })
+            .max()
+            .unwrap();
+        let packets = encoder.get_repair_only_packets(overhead);
+
+        let mut result = vec![];
+        for (source_block_number, block) in encoder.get_block_encoders().iter().enumerate() {
+            let source_block_number = source_block_number as u8;
+            let block_packets: Vec<EncodingPacket> = packets
+                .iter()
+                .filter(|packet| packet.payload_id.source_block_number() == source_block_number)
+                .cloned()
+                .collect();
+            let block_length = block.source_packets().len() as u64 * config.symbol_size() as u64;
+            let mut decoder = SourceBlockDecoder::new(source_block_number, &config, block_length);
+            let no_hdpc_decodes = NO_HDPC_DECODES.with(|decodes| decodes.get());
+            result.extend(decoder.decode(block_packets).unwrap());
+            assert_eq!(decoder.received_source_symbols(), 0);
+            assert_eq!(
+                NO_HDPC_DECODES.with(|decodes| decodes.get()),
+                no_hdpc_decodes + 1
+            );
+        }
+        result.truncate(data.len());
+        assert_eq!(result, data);
+    }
+
+    #[test]
+    fn decode_borrowed_packets() {
+        let data: Vec<u8> = (0..10_000).map(|_| rand::rng().random()).collect();
+        let encoder = Encoder::with_defaults(&data, 500);
+        let mut packets: Vec<Vec<u8>> = encoder
+            .get_encoded_packets(15)
+            .iter()
+            .map(|packet| packet.serialize())
+            .collect();
+        packets.shuffle(&mut rand::rng());
+        // Erase 10 packets at random
+        let length = packets.len();
+        packets.truncate(length - 10);
+
+        let mut decoder = Decoder::new(encoder.get_config());
+        let mut try_decoder = Decoder::new(encoder.get_config());
+        let mut result = None;
+        let mut try_result = None;
+        for packet in packets.iter() {
+            let packet = EncodingPacketRef::deserialize(packet);
+            if result.is_none() {
+                result = decoder.decode_ref(packet.clone());
+            }
+            if try_result.is_none() {
+                try_result = try_decoder.try_decode_ref(packet).unwrap();
+            }
+        }
+        assert_eq!(result.unwrap(), data);
+        assert_eq!(try_result.unwrap(), data);
+        for block_decoder in decoder.get_block_decoders() {
+            assert!(block_decoder.is_decoded());
+            assert_eq!(block_decoder.symbols_needed(), 0);
+        }
+    }
+
+    #[test]
+    fn source_block_progress() {
+        let symbol_size = 8;
+        let data: Vec<u8> = (0..symbol_size * 10)
+            .map(|_| rand::rng().random())
+            .collect();
+        let config = ObjectTransmissionInformation::new(0, symbol_size as u16, 0, 1, 1);
+        let encoder = SourceBlockEncoder::new(1, &config, &data);
+        let mut decoder = SourceBlockDecoder::new(1, &config, data.len() as u64);
+        assert_eq!(decoder.source_block_symbols(), 10);
+        assert_eq!(decoder.symbols_needed(), 10);
+        assert_eq!(
+            decoder.missing_source_symbols(),
+            (0..10).collect::<Vec<_>>()
+        );
+
+        let source_packets = encoder.source_packets();
+        let received = [1, 4, 4, 7].map(|i| source_packets[i].clone());
+        assert_eq!(decoder.decode(received), None);
+        assert_eq!(decoder.received_symbols(), 3);
+        assert_eq!(decoder.received_source_symbols(), 3);
+        assert_eq!(decoder.symbols_needed(), 7);
+        assert_eq!(decoder.missing_source_symbols(), vec![0, 2, 3, 5, 6, 8, 9]);
+        assert!(!decoder.is_decoded());
+
+        assert!(decoder.decode(encoder.repair_packets(0, 9)).is_some());
+        assert!(decoder.is_decoded());
+        assert_eq!(decoder.received_symbols(), 12);
+        assert_eq!(decoder.received_source_symbols(), 3);
+        assert_eq!(decoder.symbols_needed(), 0);
+        assert!(decoder.missing_source_symbols().is_empty());
+    }
+
+    #[test]
+    fn read_available_source_symbols() {
+        let symbol_size = 48;
+        let symbol_count = 10;
+        let data: Vec<u8> = (0..symbol_size * symbol_count)
+            .map(|_| rand::rng().random())
+            .collect();
+        for sub_blocks in [1, 4] {
+            let config =
+                ObjectTransmissionInformation::new(0, symbol_size as u16, 0, sub_blocks, 4);
+            let encoder = SourceBlockEncoder::new(0, &config, &data);
+            let mut decoder = SourceBlockDecoder::new(0, &config, data.len() as u64);
+            assert!(decoder.available_ranges().is_empty());
+
+            let source_packets = encoder.source_packets();
+            let received = [2, 3, 4, 8].map(|i| source_packets[i].clone());
+            assert_eq!(decoder.decode(received), None);
+
+            let ranges = decoder.available_ranges();
+            let expected_bytes = 4 * symbol_size;
+            assert_eq!(
+                ranges.iter().map(|(start, end)| end - start).sum::<usize>(),
+                expected_bytes
+            );
+            for (start, end) in ranges {
+                let mut buf = vec![0; end - start + 10];
+                assert_eq!(decoder.read_available(start, &mut buf), end - start);
+                assert_eq!(&buf[..end - start], &data[start..end]);
+            }
+            assert_eq!(decoder.read_available(0, &mut [0; 8]), 0);
+        }
+    }
+
+    #[test]
+    fn decoding_plan_replay() {
+        let symbol_size = 8;
+        for (symbol_count, extra_repair) in [(10, 0), (10, 15), (300, 0), (300, 30)] {
+            let lost = [1, 3, 4];
+            let repair_count = lost.len() as u32 + extra_repair;
+            let mut received_esis: Vec<u32> = (0..symbol_count)
+                .filter(|esi| !lost.contains(esi))
+                .collect();
+            received_esis.extend(symbol_count..(symbol_count + repair_count));
+            let plan = SourceBlockDecodingPlan::generate(symbol_count, &received_esis).unwrap();
+            assert_eq!(plan.source_symbol_count(), symbol_count);
+            assert_eq!(plan.received_esis(), &received_esis[..]);
+
+            // Replay the same loss pattern on different data, with and without surplus symbols
+            for surplus in [0, 2] {
+                let data: Vec<u8> = (0..symbol_size * symbol_count as usize)
+                    .map(|_| rand::rng().random())
+                    .collect();
+                let config = ObjectTransmissionInformation::new(0, symbol_size as u16, 0, 1, 1);
+                let encoder = SourceBlockEncoder::new(0, &config, &data);
+                let mut packets: Vec<EncodingPacket> = encoder
+                    .source_packets()
+                    .into_iter()
+                    .filter(|packet| !lost.contains(&packet.payload_id().encoding_symbol_id()))
+                    .collect();
+                packets.extend(encoder.repair_packets(0, repair_count + surplus));
+                packets.reverse();
+
+                let mut decoder = SourceBlockDecoder::new(0, &config, data.len() as u64);
+                assert_eq!(decoder.decode_with_plan(packets, &plan).unwrap(), data);
+            }
+        }
+
+        assert_eq!(SourceBlockDecodingPlan::generate(10, &[0, 1, 2]), None);
+    }
+
+    #[test]
+    fn decoding_plan_fallback() {
+        let symbol_size = 8;
+        let data: Vec<u8> = (0..symbol_size * 10)
+            .map(|_| rand::rng().random())
+            .collect();
+        let config = ObjectTransmissionInformation::new(0, symbol_size as u16, 0, 1, 1);
+        let encoder = SourceBlockEncoder::new(0, &config, &data);
+        // The plan needs source symbol 0, which is never received
+        let plan = SourceBlockDecodingPlan::generate(10, &(0..12).collect::<Vec<_>>()).unwrap();
+
+        let mut decoder = SourceBlockDecoder::new(0, &config, data.len() as u64);
+        let mut packets = encoder.source_packets().split_off(1);
+        packets.extend(encoder.repair_packets(0, 2));
+        assert_eq!(decoder.decode_with_plan(packets, &plan).unwrap(), data);
+        assert_eq!(decoder.received_esis(), (1..12).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn source_block_try_decode_rejects_malformed_packets() {
+        let symbol_size = 8;
+        let data: Vec<u8> = (0..symbol_size * 10)
+            .map(|_| rand::rng().random())
+            .collect();
+        let config = ObjectTransmissionInformation::new(0, symbol_size as u16, 0, 1, 1);
+        let encoder = SourceBlockEncoder::new(1, &config, &data);
+        let mut decoder = SourceBlockDecoder::new(1, &config, data.len() as u64);
+
+        let wrong_block = EncodingPacket::new(PayloadId::new(2, 0), vec![0; symbol_size]);
+        assert_eq!(
+            decoder.try_decode(iter::once(wrong_block)),
+            Err(DecodeError::InvalidSourceBlockNumber(2))
+        );
+
+        let long_payload = EncodingPacket::new(PayloadId::new(1, 20), vec![0; symbol_size + 1]);
+        assert_eq!(
+            decoder.try_decode(iter::once(long_payload)),
+            Err(DecodeError::InvalidSymbolLength {
+                expected: symbol_size,
+                actual: symbol_size + 1
+            })
+        );
+
+        let result = decoder.try_decode(encoder.repair_packets(0, 12)).unwrap();
+        assert_eq!(result.unwrap(), data);
+        assert_eq!(
+            decoder.try_decode(encoder.source_packets()),
+            Err(DecodeError::AlreadyDecoded)
+        );
+    }
+
     #[test]
     fn round_trip_dense() {
         round_trip(99_999, 100, false);
@@ -1,9 +1,9 @@
 #[cfg(feature = "std")]
-use std::collections::{HashMap, VecDeque};
+use std::io::{self, ErrorKind, Read, Write};
 #[cfg(feature = "std")]
-use std::sync::{Arc, Mutex, OnceLock};
-#[cfg(feature = "std")]
-use std::vec::Vec;
+use std::{sync::Arc, vec::Vec};
+
+use core::{fmt, mem};
 
 use crate::ObjectTransmissionInformation;
 use crate::base::EncodingPacket;
@@ -12,12 +12,16 @@ use crate::base::intermediate_tuple;
 use crate::base::partition;
 use crate::constraint_matrix::generate_constraint_matrix;
 use crate::matrix::DenseBinaryMatrix;
+use crate::octet::Octet;
 use crate::octets::add_assign;
-use crate::operation_vector::{SymbolOps, perform_op};
+use crate::operation_vector::{SymbolOps, perform_ops, with_column_threads};
 use crate::pi_solver::fused_inverse_mul_symbols;
+#[cfg(feature = "std")]
+use crate::plan_cache::{get_or_generate_plan, insert_plan};
 use crate::sparse_matrix::SparseBinaryMatrix;
 use crate::symbol::Symbol;
 use crate::symbol_slab::SymbolSlab;
+use crate::systematic_constants::MAX_SOURCE_SYMBOLS_PER_BLOCK;
 use crate::systematic_constants::extended_source_block_symbols;
 use crate::systematic_constants::num_hdpc_symbols;
 use crate::systematic_constants::num_intermediate_symbols;
@@ -25,7 +29,9 @@ use crate::systematic_constants::num_ldpc_symbols;
 use crate::systematic_constants::num_lt_symbols;
 use crate::systematic_constants::num_pi_symbols;
 use crate::systematic_constants::{calculate_p1, systematic_index};
+use crate::util::crc32;
 use crate::util::int_div_ceil;
+use crate::util::parallel_map;
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 #[cfg(feature = "serde_support")]
@@ -33,11 +39,16 @@ use serde::{Deserialize, Serialize};
 
 pub const SPARSE_MATRIX_THRESHOLD: u32 = 250;
 
+// Encoding Symbol IDs are 24-bit unsigned ints, see section 3.2
+pub(crate) const MAX_ENCODING_SYMBOL_ID: u32 = (1 << 24) - 1;
+
 #[derive(Default, Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
 pub struct EncoderBuilder {
     decoder_memory_requirement: u64,
     max_packet_size: u16,
+    #[cfg(feature = "parallel")]
+    column_threads: usize,
 }
 
 impl EncoderBuilder {
@@ -45,6 +56,8 @@ impl EncoderBuilder {
         EncoderBuilder {
             decoder_memory_requirement: 10 * 1024 * 1024,
             max_packet_size: 1024,
+            #[cfg(feature = "parallel")]
+            column_threads: 1,
         }
     }
 
@@ -56,14 +69,33 @@ impl EncoderBuilder {
         self.max_packet_size = bytes;
     }
 
-    pub fn build(&self, data: &[u8]) -> Encoder {
-        let config = ObjectTransmissionInformation::generate_encoding_parameters(
-            data.len() as u64,
+    /// Splits each symbol column-wise across up to `threads` threads while generating the
+    /// intermediate symbols of a block. This only applies when the object has a single source
+    /// block, since multiple blocks are already encoded in parallel, so it is disabled (1) by
+    /// default.
+    #[cfg(feature = "parallel")]
+    pub fn set_column_threads(&mut self, threads: usize) {
+        self.column_threads = threads;
+    }
+
+    /// The configuration `build` uses for an object of `transfer_length` bytes. Useful for
+    /// encoding an object with `StreamingEncoder` instead of holding it in memory.
+    pub fn build_config(&self, transfer_length: u64) -> ObjectTransmissionInformation {
+        ObjectTransmissionInformation::generate_encoding_parameters(
+            transfer_length,
             self.max_packet_size,
             self.decoder_memory_requirement,
-        );
+        )
+    }
 
-        Encoder::new(data, config)
+    pub fn build(&self, data: &[u8]) -> Encoder {
+        let config = self.build_config(data.len() as u64);
+
+        #[cfg(feature = "parallel")]
+        let column_threads = self.column_threads;
+        #[cfg(not(feature = "parallel"))]
+        let column_threads = 1;
+        with_column_threads(column_threads, || Encoder::new(data, config))
     }
 }
 
@@ -72,6 +104,13 @@ impl EncoderBuilder {
 pub fn calculate_block_offsets(
     data: &[u8],
     config: &ObjectTransmissionInformation,
+) -> Vec<(usize, usize)> {
+    calculate_block_offsets_for_length(data.len(), config)
+}
+
+pub(crate) fn calculate_block_offsets_for_length(
+    data_length: usize,
+    config: &ObjectTransmissionInformation,
 ) -> Vec<(usize, usize)> {
     let kt = int_div_ceil(config.transfer_length(), config.symbol_size() as u64);
 
@@ -90,9 +129,9 @@ pub fn calculate_block_offsets(
     if zs > 0 {
         for _ in zl..(zl + zs) {
             let offset = ks as usize * config.symbol_size() as usize;
-            if data_index + offset > data.len() {
+            if data_index + offset > data_length {
                 // Should only be possible when Kt * T > F. See third to last paragraph in section 4.4.1.2
-                assert!(kt as usize * config.symbol_size() as usize > data.len());
+                assert!(kt as usize * config.symbol_size() as usize > data_length);
             }
             blocks.push((data_index, (data_index + offset)));
             data_index += offset;
@@ -111,9 +150,18 @@ pub struct Encoder {
 
 impl Encoder {
     pub fn new(data: &[u8], config: ObjectTransmissionInformation) -> Encoder {
-        let mut block_encoders = vec![];
-        let mut cached_plan: Option<SourceBlockEncodingPlan> = None;
-        for (i, (start, end)) in calculate_block_offsets(data, &config).drain(..).enumerate() {
+        let block_offsets = calculate_block_offsets(data, &config);
+        // Blocks have at most two distinct sizes, so each plan is shared by many blocks
+        let mut symbol_counts: Vec<u16> = block_offsets
+            .iter()
+            .map(|(start, end)| ((end - start) / config.symbol_size() as usize) as u16)
+            .collect();
+        symbol_counts.dedup();
+        let plans = parallel_map(&symbol_counts, |_, &symbol_count| {
+            encoding_plan(symbol_count)
+        });
+
+        let block_encoders = parallel_map(&block_offsets, |i, &(start, end)| {
             // Zero pad if necessary
             let mut padded;
             let block: &[u8] = if end > data.len() {
@@ -123,21 +171,13 @@ impl Encoder {
             } else {
                 &data[start..end]
             };
-
-            let symbol_count = block.len() / config.symbol_size() as usize;
-            if cached_plan.is_none()
-                || cached_plan.as_ref().unwrap().source_symbol_count != symbol_count as u16
-            {
-                let plan = SourceBlockEncodingPlan::generate(symbol_count as u16);
-                cached_plan = Some(plan);
-            }
-            block_encoders.push(SourceBlockEncoder::with_encoding_plan(
-                i as u8,
-                &config,
-                block,
-                cached_plan.as_ref().unwrap(),
-            ));
-        }
+            let symbol_count = (block.len() / config.symbol_size() as usize) as u16;
+            let plan = &plans[symbol_counts
+                .iter()
+                .position(|count| *count == symbol_count)
+                .unwrap()];
+            SourceBlockEncoder::with_encoding_plan(i as u8, &config, block, plan)
+        });
 
         Encoder {
             config,
@@ -159,12 +199,53 @@ impl Encoder {
     }
 
     pub fn get_encoded_packets(&self, repair_packets_per_block: u32) -> Vec<EncodingPacket> {
-        let mut packets = vec![];
-        for encoder in self.blocks.iter() {
-            packets.extend(encoder.source_packets());
+        parallel_map(&self.blocks, |_, encoder| {
+            let mut packets = encoder.source_packets();
             packets.extend(encoder.repair_packets(0, repair_packets_per_block));
+            packets
+        })
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Returns only repair packets, `K + overhead_per_block` for each block of `K` source
+    /// symbols, starting at encoding symbol ID `K`. No source symbol is ever sent, so the object
+    /// can only be recovered by decoding. One or two extra symbols per block make decoding
+    /// failure unlikely, and larger overheads let the decoder skip the GF(256) HDPC rows.
+    pub fn get_repair_only_packets(&self, overhead_per_block: u32) -> Vec<EncodingPacket> {
+        parallel_map(&self.blocks, |_, encoder| {
+            let source_symbols = encoder.source_symbols.len() as u32;
+            encoder.repair_packets(0, source_symbols + overhead_per_block)
+        })
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Returns an iterator over the source packets of every block, followed by an unbounded
+    /// stream of repair packets which cycles through the blocks one packet at a time.
+    pub fn encoded_packet_iter(&self) -> EncodedPacketIter<'_> {
+        EncodedPacketIter {
+            blocks: &self.blocks,
+            source_block: 0,
+            source_symbol: 0,
+            repair_iters: self
+                .blocks
+                .iter()
+                .map(|block| block.repair_packet_iter(0))
+                .collect(),
+            repair_block: 0,
+        }
+    }
+
+    /// Like `encoded_packet_iter`, but skips the source packets, so the stream only contains
+    /// repair packets cycling through the blocks.
+    pub fn repair_only_packet_iter(&self) -> EncodedPacketIter<'_> {
+        EncodedPacketIter {
+            source_block: self.blocks.len(),
+            ..self.encoded_packet_iter()
         }
-        packets
     }
 
     pub fn get_block_encoders(&self) -> &Vec<SourceBlockEncoder> {
@@ -179,6 +260,90 @@ pub struct SourceBlockEncodingPlan {
     source_symbol_count: u16,
 }
 
+const PLAN_MAGIC: [u8; 4] = *b"RQEP";
+const PLAN_FORMAT_VERSION: u8 = 1;
+// Magic, version, and symbol count
+const PLAN_HEADER_BYTES: usize = 7;
+
+const PLAN_OP_ADD_ASSIGN: u8 = 0;
+const PLAN_OP_MUL_ASSIGN: u8 = 1;
+const PLAN_OP_FMA: u8 = 2;
+const PLAN_OP_REORDER: u8 = 3;
+
+/// Reason a serialized `SourceBlockEncodingPlan` was rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SourceBlockEncodingPlanError {
+    /// The data does not start with the plan magic bytes.
+    InvalidMagic,
+    /// The plan was serialized with an unsupported format version.
+    UnsupportedVersion(u8),
+    /// The checksum does not match the contents, so the plan is corrupt.
+    ChecksumMismatch,
+    /// The data ended in the middle of the plan.
+    Truncated,
+    /// The source symbol count is zero or exceeds K'_max.
+    InvalidSymbolCount(u16),
+    /// An operation is malformed or refers to a symbol outside the block.
+    InvalidOperation,
+    /// The plan is for a different number of source symbols than expected.
+    UnexpectedSymbolCount { expected: u16, actual: u16 },
+}
+
+impl fmt::Display for SourceBlockEncodingPlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceBlockEncodingPlanError::InvalidMagic => write!(f, "not an encoding plan"),
+            SourceBlockEncodingPlanError::UnsupportedVersion(version) => {
+                write!(f, "unsupported encoding plan version {version}")
+            }
+            SourceBlockEncodingPlanError::ChecksumMismatch => {
+                write!(f, "encoding plan checksum mismatch")
+            }
+            SourceBlockEncodingPlanError::Truncated => write!(f, "encoding plan is truncated"),
+            SourceBlockEncodingPlanError::InvalidSymbolCount(count) => {
+                write!(f, "invalid source symbol count {count}")
+            }
+            SourceBlockEncodingPlanError::InvalidOperation => {
+                write!(f, "encoding plan contains an invalid operation")
+            }
+            SourceBlockEncodingPlanError::UnexpectedSymbolCount { expected, actual } => write!(
+                f,
+                "expected plan for {expected} source symbols, got {actual}"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SourceBlockEncodingPlanError {}
+
+struct PlanReader<'a> {
+    data: &'a [u8],
+}
+
+impl PlanReader<'_> {
+    fn read<const N: usize>(&mut self) -> Result<[u8; N], SourceBlockEncodingPlanError> {
+        if self.data.len() < N {
+            return Err(SourceBlockEncodingPlanError::Truncated);
+        }
+        let (bytes, rest) = self.data.split_at(N);
+        self.data = rest;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    fn read_u8(&mut self) -> Result<u8, SourceBlockEncodingPlanError> {
+        Ok(self.read::<1>()?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16, SourceBlockEncodingPlanError> {
+        Ok(u16::from_be_bytes(self.read()?))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, SourceBlockEncodingPlanError> {
+        Ok(u32::from_be_bytes(self.read()?))
+    }
+}
+
 impl SourceBlockEncodingPlan {
     // Generates an encoding plan that is valid for any combination of data length and symbol size
     // where ceil(data_length / symbol_size) = symbol_count
@@ -191,56 +356,226 @@ impl SourceBlockEncodingPlan {
             source_symbol_count: symbol_count,
         }
     }
-}
 
-#[cfg(feature = "std")]
-const SOURCE_BLOCK_ENCODING_PLAN_CACHE_CAPACITY: usize = 64;
+    /// Number of source symbols the plan was generated for.
+    pub fn source_symbol_count(&self) -> u16 {
+        self.source_symbol_count
+    }
 
-#[cfg(feature = "std")]
-#[derive(Default)]
-struct SourceBlockEncodingPlanCache {
-    plans: HashMap<u16, Arc<SourceBlockEncodingPlan>>,
-    insertion_order: VecDeque<u16>,
-}
+    /// Approximate memory used by the plan.
+    pub fn size_in_bytes(&self) -> usize {
+        let mut bytes = size_of::<Self>() + size_of::<SymbolOps>() * self.operations.len();
+        for op in self.operations.iter() {
+            if let SymbolOps::Reorder { order } = op {
+                bytes += size_of::<usize>() * order.len();
+            }
+        }
+        bytes
+    }
 
-#[cfg(feature = "std")]
-fn source_block_encoding_plan_cache() -> &'static Mutex<SourceBlockEncodingPlanCache> {
-    static CACHE: OnceLock<Mutex<SourceBlockEncodingPlanCache>> = OnceLock::new();
-    CACHE.get_or_init(|| Mutex::new(SourceBlockEncodingPlanCache::default()))
-}
+    /// Serializes the plan into a compact, versioned binary format, which is independent of serde.
+    ///
+    /// The format is: the magic bytes "RQEP", a format version byte, the source symbol count,
+    /// the number of operations, the operations, and a trailing CRC-32 of everything before it.
+    /// All integers are big-endian. Symbol indices fit in 16 bits, since there are fewer than
+    /// 65536 intermediate symbols.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut serialized = Vec::with_capacity(PLAN_HEADER_BYTES + 8 + 6 * self.operations.len());
+        serialized.extend_from_slice(&PLAN_MAGIC);
+        serialized.push(PLAN_FORMAT_VERSION);
+        serialized.extend_from_slice(&self.source_symbol_count.to_be_bytes());
+        serialized.extend_from_slice(&(self.operations.len() as u32).to_be_bytes());
+        for op in self.operations.iter() {
+            match op {
+                SymbolOps::AddAssign { dest, src } => {
+                    serialized.push(PLAN_OP_ADD_ASSIGN);
+                    serialized.extend_from_slice(&(*dest as u16).to_be_bytes());
+                    serialized.extend_from_slice(&(*src as u16).to_be_bytes());
+                }
+                SymbolOps::MulAssign { dest, scalar } => {
+                    serialized.push(PLAN_OP_MUL_ASSIGN);
+                    serialized.extend_from_slice(&(*dest as u16).to_be_bytes());
+                    serialized.push(scalar.byte());
+                }
+                SymbolOps::FMA { dest, src, scalar } => {
+                    serialized.push(PLAN_OP_FMA);
+                    serialized.extend_from_slice(&(*dest as u16).to_be_bytes());
+                    serialized.extend_from_slice(&(*src as u16).to_be_bytes());
+                    serialized.push(scalar.byte());
+                }
+                SymbolOps::Reorder { order } => {
+                    serialized.push(PLAN_OP_REORDER);
+                    for index in order.iter() {
+                        serialized.extend_from_slice(&(*index as u16).to_be_bytes());
+                    }
+                }
+            }
+        }
+        let checksum = crc32(&serialized);
+        serialized.extend_from_slice(&checksum.to_be_bytes());
+        serialized
+    }
 
-#[cfg(feature = "std")]
-fn get_or_generate_source_block_encoding_plan(symbol_count: u16) -> Arc<SourceBlockEncodingPlan> {
-    {
-        let cache = source_block_encoding_plan_cache();
-        let guard = cache
-            .lock()
-            .unwrap_or_else(|poisoned| poisoned.into_inner());
-        if let Some(plan) = guard.plans.get(&symbol_count) {
-            return Arc::clone(plan);
+    /// Parses a plan produced by `serialize`. The checksum is verified, and every operation is
+    /// checked to only refer to intermediate symbols of the plan's source symbol count, and every
+    /// reordering to be a permutation of them.
+    pub fn deserialize(
+        data: &[u8],
+    ) -> Result<SourceBlockEncodingPlan, SourceBlockEncodingPlanError> {
+        Ok(SourceBlockEncodingPlan::deserialize_prefix(data)?.0)
+    }
+
+    /// Like `deserialize`, but additionally rejects plans which are not for `symbol_count` source
+    /// symbols.
+    pub fn deserialize_for(
+        data: &[u8],
+        symbol_count: u16,
+    ) -> Result<SourceBlockEncodingPlan, SourceBlockEncodingPlanError> {
+        let plan = SourceBlockEncodingPlan::deserialize(data)?;
+        if plan.source_symbol_count != symbol_count {
+            return Err(SourceBlockEncodingPlanError::UnexpectedSymbolCount {
+                expected: symbol_count,
+                actual: plan.source_symbol_count,
+            });
         }
+        Ok(plan)
     }
 
-    let generated = Arc::new(SourceBlockEncodingPlan::generate(symbol_count));
-    let cache = source_block_encoding_plan_cache();
-    let mut guard = cache
-        .lock()
-        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    // Parses a plan from the start of `data`, and returns it along with its length in bytes
+    fn deserialize_prefix(
+        data: &[u8],
+    ) -> Result<(SourceBlockEncodingPlan, usize), SourceBlockEncodingPlanError> {
+        let mut reader = PlanReader { data };
+        if reader.read::<4>()? != PLAN_MAGIC {
+            return Err(SourceBlockEncodingPlanError::InvalidMagic);
+        }
+        let version = reader.read_u8()?;
+        if version != PLAN_FORMAT_VERSION {
+            return Err(SourceBlockEncodingPlanError::UnsupportedVersion(version));
+        }
+        let symbol_count = reader.read_u16()?;
+        if symbol_count == 0 || symbol_count as u32 > MAX_SOURCE_SYMBOLS_PER_BLOCK {
+            return Err(SourceBlockEncodingPlanError::InvalidSymbolCount(
+                symbol_count,
+            ));
+        }
+        let intermediate_symbols = num_intermediate_symbols(symbol_count as u32) as usize;
+        let read_index = |reader: &mut PlanReader| -> Result<usize, SourceBlockEncodingPlanError> {
+            let index = reader.read_u16()? as usize;
+            if index >= intermediate_symbols {
+                return Err(SourceBlockEncodingPlanError::InvalidOperation);
+            }
+            Ok(index)
+        };
+
+        let num_operations = reader.read_u32()?;
+        let mut operations = vec![];
+        for _ in 0..num_operations {
+            let op = match reader.read_u8()? {
+                PLAN_OP_ADD_ASSIGN => SymbolOps::AddAssign {
+                    dest: read_index(&mut reader)?,
+                    src: read_index(&mut reader)?,
+                },
+                PLAN_OP_MUL_ASSIGN => SymbolOps::MulAssign {
+                    dest: read_index(&mut reader)?,
+                    scalar: Octet::new(reader.read_u8()?),
+                },
+                PLAN_OP_FMA => SymbolOps::FMA {
+                    dest: read_index(&mut reader)?,
+                    src: read_index(&mut reader)?,
+                    scalar: Octet::new(reader.read_u8()?),
+                },
+                PLAN_OP_REORDER => SymbolOps::Reorder {
+                    order: (0..intermediate_symbols)
+                        .map(|_| read_index(&mut reader))
+                        .collect::<Result<_, _>>()?,
+                },
+                _ => return Err(SourceBlockEncodingPlanError::InvalidOperation),
+            };
+            if let SymbolOps::AddAssign { dest, src } | SymbolOps::FMA { dest, src, .. } = op
+                && dest == src
+            {
+                return Err(SourceBlockEncodingPlanError::InvalidOperation);
+            }
+            // A reordering which repeats an index would alias two symbols
+            if let SymbolOps::Reorder { ref order } = op {
+                let mut seen = vec![false; intermediate_symbols];
+                for &index in order.iter() {
+                    if mem::replace(&mut seen[index], true) {
+                        return Err(SourceBlockEncodingPlanError::InvalidOperation);
+                    }
+                }
+            }
+            operations.push(op);
+        }
+
+        let length = data.len() - reader.data.len();
+        let checksum = reader.read_u32()?;
+        if checksum != crc32(&data[..length]) {
+            return Err(SourceBlockEncodingPlanError::ChecksumMismatch);
+        }
 
-    if let Some(plan) = guard.plans.get(&symbol_count) {
-        return Arc::clone(plan);
+        let plan = SourceBlockEncodingPlan {
+            operations,
+            source_symbol_count: symbol_count,
+        };
+        Ok((plan, length + 4))
     }
+}
+
+#[cfg(feature = "std")]
+const PLAN_LIBRARY_MAGIC: [u8; 4] = *b"RQPL";
 
-    if guard.plans.len() >= SOURCE_BLOCK_ENCODING_PLAN_CACHE_CAPACITY
-        && let Some(evicted_symbol_count) = guard.insertion_order.pop_front()
-    {
-        guard.plans.remove(&evicted_symbol_count);
+/// Generates plans for each of `symbol_counts` and writes them to `writer` as a plan library,
+/// which can later be loaded with `preload_encoding_plan_library`.
+///
+/// The library consists of the magic bytes "RQPL", the number of plans as a big-endian u32, and
+/// then each plan as produced by `SourceBlockEncodingPlan::serialize`.
+#[cfg(feature = "std")]
+pub fn write_encoding_plan_library<W: Write>(
+    symbol_counts: &[u16],
+    mut writer: W,
+) -> io::Result<()> {
+    writer.write_all(&PLAN_LIBRARY_MAGIC)?;
+    writer.write_all(&(symbol_counts.len() as u32).to_be_bytes())?;
+    for &symbol_count in symbol_counts {
+        writer.write_all(&SourceBlockEncodingPlan::generate(symbol_count).serialize())?;
     }
+    writer.flush()
+}
 
-    guard.insertion_order.push_back(symbol_count);
-    guard.plans.insert(symbol_count, Arc::clone(&generated));
-    generated
+/// Reads a plan library written by `write_encoding_plan_library` and inserts its plans into the
+/// global plan cache used by `SourceBlockEncoder::new`. Returns the number of plans loaded.
+///
+/// The whole library is validated before any plan is inserted, so a corrupt library leaves the
+/// cache untouched. Errors are of kind `InvalidData`, wrapping a `SourceBlockEncodingPlanError`.
+/// Note that the cache may evict plans once it is full, see `encoding_plan_cache`.
+#[cfg(feature = "std")]
+pub fn preload_encoding_plan_library<R: Read>(mut reader: R) -> io::Result<usize> {
+    let mut data = vec![];
+    reader.read_to_end(&mut data)?;
+    let invalid = |err: SourceBlockEncodingPlanError| io::Error::new(ErrorKind::InvalidData, err);
+
+    let mut plan_reader = PlanReader { data: &data };
+    if plan_reader.read::<4>().map_err(invalid)? != PLAN_LIBRARY_MAGIC {
+        return Err(invalid(SourceBlockEncodingPlanError::InvalidMagic));
+    }
+    let num_plans = plan_reader.read_u32().map_err(invalid)?;
+    let mut remaining = plan_reader.data;
+    let mut plans = vec![];
+    for _ in 0..num_plans {
+        let (plan, length) =
+            SourceBlockEncodingPlan::deserialize_prefix(remaining).map_err(invalid)?;
+        remaining = &remaining[length..];
+        plans.push(plan);
+    }
+
+    for plan in plans.drain(..) {
+        insert_plan(plan);
+    }
+    Ok(num_plans as usize)
 }
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
 pub struct SourceBlockEncoder {
@@ -294,7 +629,7 @@ impl SourceBlockEncoder {
                 source_symbols.len() <= u16::MAX as usize,
                 "source_symbols.len() exceeds u16::MAX"
             );
-            let plan = get_or_generate_source_block_encoding_plan(source_symbols.len() as u16);
+            let plan = get_or_generate_plan(source_symbols.len() as u16);
             let intermediate_symbols = gen_intermediate_symbols_with_plan(
                 &source_symbols,
                 config.symbol_size() as usize,
@@ -348,45 +683,177 @@ impl SourceBlockEncoder {
 
     pub fn source_packets(&self) -> Vec<EncodingPacket> {
         (0..self.source_symbols.len())
-            .map(|i| {
-                EncodingPacket::new(
-                    PayloadId::new(self.source_block_id, i as u32),
-                    self.source_symbols[i].as_bytes().to_vec(),
-                )
-            })
+            .map(|i| self.source_packet(i))
             .collect()
     }
 
+    fn source_packet(&self, source_symbol_id: usize) -> EncodingPacket {
+        EncodingPacket::new(
+            PayloadId::new(self.source_block_id, source_symbol_id as u32),
+            self.source_symbols[source_symbol_id].as_bytes().to_vec(),
+        )
+    }
+
     // See section 5.3.4
     pub fn repair_packets(&self, start_repair_symbol_id: u32, packets: u32) -> Vec<EncodingPacket> {
-        let start_encoding_symbol_id = start_repair_symbol_id
-            + extended_source_block_symbols(self.source_symbols.len() as u32);
-        let mut result = vec![];
-        let lt_symbols = num_lt_symbols(self.source_symbols.len() as u32);
-        let sys_index = systematic_index(self.source_symbols.len() as u32);
-        let p1 = calculate_p1(self.source_symbols.len() as u32);
-        let symbol_size = self.intermediate_symbols.symbol_size();
-        for i in 0..packets {
-            let tuple = intermediate_tuple(start_encoding_symbol_id + i, lt_symbols, sys_index, p1);
-            let mut data = vec![0u8; symbol_size];
-            enc_into(
-                &mut data,
-                self.source_symbols.len() as u32,
-                &self.intermediate_symbols,
-                tuple,
-            );
-            result.push(EncodingPacket::new(
-                PayloadId::new(
-                    self.source_block_id,
-                    self.source_symbols.len() as u32 + start_repair_symbol_id + i,
-                ),
-                data,
-            ));
+        let params = RepairParameters::new(self.source_symbols.len() as u32);
+        (0..packets)
+            .map(|i| self.repair_packet(start_repair_symbol_id + i, params))
+            .collect()
+    }
+
+    /// Returns an iterator which lazily generates repair packets, starting from
+    /// `start_repair_symbol_id`, until the 24-bit encoding symbol ID space is exhausted.
+    pub fn repair_packet_iter(&self, start_repair_symbol_id: u32) -> RepairPacketIter<'_> {
+        RepairPacketIter {
+            encoder: self,
+            params: RepairParameters::new(self.source_symbols.len() as u32),
+            next_repair_symbol_id: start_repair_symbol_id,
         }
-        result
+    }
+
+    /// Writes the repair symbol with encoding symbol ID `encoding_symbol_id` into `out`, without
+    /// allocating.
+    ///
+    /// `encoding_symbol_id` must not refer to a source symbol, and `out` must be exactly one
+    /// symbol long.
+    pub fn encode_repair_symbol_into(&self, encoding_symbol_id: u32, out: &mut [u8]) {
+        let source_symbols = self.source_symbols.len() as u32;
+        assert!(encoding_symbol_id >= source_symbols);
+        assert!(encoding_symbol_id <= MAX_ENCODING_SYMBOL_ID);
+        assert_eq!(out.len(), self.intermediate_symbols.symbol_size());
+        self.repair_symbol_into(
+            out,
+            encoding_symbol_id - source_symbols,
+            RepairParameters::new(source_symbols),
+        );
+    }
+
+    /// Writes a serialized repair packet, that is the FEC Payload ID followed by the repair
+    /// symbol, into the start of `out` without allocating. Returns the number of bytes written.
+    ///
+    /// The output is identical to `EncodingPacket::serialize` of the corresponding repair packet.
+    pub fn encode_repair_packet_into(&self, encoding_symbol_id: u32, out: &mut [u8]) -> usize {
+        let packet_size = 4 + self.intermediate_symbols.symbol_size();
+        assert!(out.len() >= packet_size);
+        let payload_id = PayloadId::new(self.source_block_id, encoding_symbol_id);
+        out[..4].copy_from_slice(&payload_id.serialize());
+        self.encode_repair_symbol_into(encoding_symbol_id, &mut out[4..packet_size]);
+        packet_size
+    }
+
+    fn repair_packet(&self, repair_symbol_id: u32, params: RepairParameters) -> EncodingPacket {
+        let source_symbols = self.source_symbols.len() as u32;
+        let mut data = vec![0u8; self.intermediate_symbols.symbol_size()];
+        self.repair_symbol_into(&mut data, repair_symbol_id, params);
+        EncodingPacket::new(
+            PayloadId::new(self.source_block_id, source_symbols + repair_symbol_id),
+            data,
+        )
+    }
+
+    fn repair_symbol_into(&self, dest: &mut [u8], repair_symbol_id: u32, params: RepairParameters) {
+        let source_symbols = self.source_symbols.len() as u32;
+        let internal_symbol_id = extended_source_block_symbols(source_symbols) + repair_symbol_id;
+        let tuple = intermediate_tuple(
+            internal_symbol_id,
+            params.lt_symbols,
+            params.sys_index,
+            params.p1,
+        );
+        enc_into(dest, source_symbols, &self.intermediate_symbols, tuple);
     }
 }
 
+#[derive(Copy, Clone, Debug)]
+struct RepairParameters {
+    lt_symbols: u32,
+    sys_index: u32,
+    p1: u32,
+}
+
+impl RepairParameters {
+    fn new(source_symbols: u32) -> RepairParameters {
+        RepairParameters {
+            lt_symbols: num_lt_symbols(source_symbols),
+            sys_index: systematic_index(source_symbols),
+            p1: calculate_p1(source_symbols),
+        }
+    }
+}
+
+/// Lazily generates the repair packets of a source block. See `SourceBlockEncoder::repair_packet_iter`.
+#[derive(Clone, Debug)]
+pub struct RepairPacketIter<'a> {
+    encoder: &'a SourceBlockEncoder,
+    params: RepairParameters,
+    next_repair_symbol_id: u32,
+}
+
+impl Iterator for RepairPacketIter<'_> {
+    type Item = EncodingPacket;
+
+    fn next(&mut self) -> Option<EncodingPacket> {
+        let source_symbols = self.encoder.source_symbols.len() as u32;
+        let encoding_symbol_id = source_symbols.checked_add(self.next_repair_symbol_id)?;
+        if encoding_symbol_id > MAX_ENCODING_SYMBOL_ID {
+            return None;
+        }
+        let packet = self
+            .encoder
+            .repair_packet(self.next_repair_symbol_id, self.params);
+        self.next_repair_symbol_id += 1;
+        Some(packet)
+    }
+}
+
+/// Lazily generates the packets of every source block of an object. See
+/// `Encoder::encoded_packet_iter`.
+#[derive(Clone, Debug)]
+pub struct EncodedPacketIter<'a> {
+    blocks: &'a [SourceBlockEncoder],
+    source_block: usize,
+    source_symbol: usize,
+    repair_iters: Vec<RepairPacketIter<'a>>,
+    repair_block: usize,
+}
+
+impl Iterator for EncodedPacketIter<'_> {
+    type Item = EncodingPacket;
+
+    fn next(&mut self) -> Option<EncodingPacket> {
+        while let Some(block) = self.blocks.get(self.source_block) {
+            if self.source_symbol < block.source_symbols.len() {
+                self.source_symbol += 1;
+                return Some(block.source_packet(self.source_symbol - 1));
+            }
+            self.source_block += 1;
+            self.source_symbol = 0;
+        }
+
+        for _ in 0..self.repair_iters.len() {
+            let block = self.repair_block;
+            self.repair_block = (self.repair_block + 1) % self.repair_iters.len();
+            if let Some(packet) = self.repair_iters[block].next() {
+                return Some(packet);
+            }
+        }
+        None
+    }
+}
+
+// Returns the plan for blocks of `symbol_count` source symbols, through the plan cache if there is
+// one
+#[cfg(feature = "std")]
+fn encoding_plan(symbol_count: u16) -> Arc<SourceBlockEncodingPlan> {
+    get_or_generate_plan(symbol_count)
+}
+
+#[cfg(not(feature = "std"))]
+fn encoding_plan(symbol_count: u16) -> SourceBlockEncodingPlan {
+    SourceBlockEncodingPlan::generate(symbol_count)
+}
+
 #[allow(non_snake_case)]
 fn create_d(
     source_block: &[Symbol],
@@ -440,9 +907,7 @@ fn gen_intermediate_symbols_with_plan(
     let extended_source_symbols = extended_source_block_symbols(source_block.len() as u32);
     let mut D = create_d(source_block, symbol_size, extended_source_symbols as usize);
 
-    for op in operation_vector {
-        perform_op(op, &mut D);
-    }
+    perform_ops(operation_vector, &mut D);
     D
 }
 
@@ -494,6 +959,7 @@ mod tests {
     use std::vec::Vec;
 
     use super::*;
+    use crate::plan_cache::lock_global_cache_for_test;
 
     use crate::PayloadId;
     use crate::base::intermediate_tuple;
@@ -503,9 +969,7 @@ mod tests {
     use crate::systematic_constants::{
         MAX_SOURCE_SYMBOLS_PER_BLOCK, calculate_p1, num_ldpc_symbols, systematic_index,
     };
-    #[cfg(not(feature = "python"))]
     use crate::{Encoder, EncoderBuilder, EncodingPacket, ObjectTransmissionInformation};
-    #[cfg(not(feature = "python"))]
     use std::collections::HashSet;
 
     const SYMBOL_SIZE: usize = 4;
@@ -633,7 +1097,60 @@ mod tests {
         );
     }
 
-    #[cfg(not(feature = "python"))]
+    #[test]
+    fn repair_packet_iter() {
+        let data = gen_test_data(SYMBOL_SIZE * NUM_SYMBOLS as usize);
+        let config = ObjectTransmissionInformation::new(0, SYMBOL_SIZE as u16, 1, 1, 1);
+        let encoder = SourceBlockEncoder::new(0, &config, &data);
+        assert_eq!(
+            encoder.repair_packet_iter(7).take(20).collect::<Vec<_>>(),
+            encoder.repair_packets(7, 20)
+        );
+
+        // Iteration ends once the 24-bit ESI space is exhausted
+        let last_repair_symbol_id = MAX_ENCODING_SYMBOL_ID - NUM_SYMBOLS;
+        let tail: Vec<_> = encoder
+            .repair_packet_iter(last_repair_symbol_id - 2)
+            .collect();
+        assert_eq!(tail, encoder.repair_packets(last_repair_symbol_id - 2, 3));
+        assert_eq!(encoder.repair_packet_iter(u32::MAX).next(), None);
+    }
+
+    #[test]
+    fn encode_repair_into_buffers() {
+        let data = gen_test_data(SYMBOL_SIZE * NUM_SYMBOLS as usize);
+        let config = ObjectTransmissionInformation::new(0, SYMBOL_SIZE as u16, 1, 1, 1);
+        let encoder = SourceBlockEncoder::new(3, &config, &data);
+
+        let mut symbol = [0u8; SYMBOL_SIZE];
+        let mut packet = [0u8; 64];
+        for expected in encoder.repair_packets(5, 10) {
+            let esi = expected.payload_id().encoding_symbol_id();
+            encoder.encode_repair_symbol_into(esi, &mut symbol);
+            assert_eq!(&symbol[..], expected.data());
+
+            let written = encoder.encode_repair_packet_into(esi, &mut packet);
+            assert_eq!(&packet[..written], &expected.serialize()[..]);
+        }
+    }
+
+    #[test]
+    fn encoded_packet_iter() {
+        let data = gen_test_data(120);
+        let config = ObjectTransmissionInformation::new(120, 10, 3, 1, 2);
+        let encoder = Encoder::new(&data, config);
+        let blocks = encoder.get_block_encoders();
+
+        let mut expected = encoder.get_encoded_packets(0);
+        for i in 0..5 {
+            for block in blocks.iter() {
+                expected.extend(block.repair_packets(i, 1));
+            }
+        }
+        let packets: Vec<_> = encoder.encoded_packet_iter().take(expected.len()).collect();
+        assert_eq!(packets, expected);
+    }
+
     #[test]
     fn test_builder() {
         let data = vec![0, 1, 2, 3];
@@ -643,7 +1160,6 @@ mod tests {
         assert_eq!(builder.build(&data), encoder);
     }
 
-    #[cfg(not(feature = "python"))]
     #[test]
     fn padding_constraint_exact() {
         let packet_size: u16 = 1024;
@@ -652,7 +1168,6 @@ mod tests {
         padding_constraint(packet_size, padding_size, data_size);
     }
 
-    #[cfg(not(feature = "python"))]
     #[test]
     fn padding_constraint_42_bytes() {
         let packet_size: u16 = 1024;
@@ -661,7 +1176,6 @@ mod tests {
         padding_constraint(packet_size, padding_size, data_size);
     }
 
-    #[cfg(not(feature = "python"))]
     fn padding_constraint(packet_size: u16, padding_size: usize, data_size: usize) {
         let data = gen_test_data(data_size);
         let encoder = Encoder::with_defaults(&data, packet_size);
@@ -682,7 +1196,112 @@ mod tests {
         assert_eq!(data[..], padded_data[..data_size]);
     }
 
-    #[cfg(not(feature = "python"))]
+    #[test]
+    fn encoder_matches_block_encoders() {
+        let data = gen_test_data(100_000);
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 96, 7, 1, 8);
+        let encoder = Encoder::new(&data, config);
+
+        let mut expected_blocks = vec![];
+        let mut expected_packets = vec![];
+        for (i, (start, end)) in calculate_block_offsets(&data, &config)
+            .into_iter()
+            .enumerate()
+        {
+            let mut block = data[start..end.min(data.len())].to_vec();
+            block.resize(end - start, 0);
+            let block_encoder = SourceBlockEncoder::new(i as u8, &config, &block);
+            expected_packets.extend(block_encoder.source_packets());
+            expected_packets.extend(block_encoder.repair_packets(0, 3));
+            expected_blocks.push(block_encoder);
+        }
+        assert_eq!(encoder.get_block_encoders(), &expected_blocks);
+        assert_eq!(encoder.get_encoded_packets(3), expected_packets);
+    }
+
+    #[test]
+    fn encoding_plan_serialization() {
+        let plan = SourceBlockEncodingPlan::generate(NUM_SYMBOLS as u16);
+        let serialized = plan.serialize();
+        assert_eq!(
+            SourceBlockEncodingPlan::deserialize(&serialized).unwrap(),
+            plan
+        );
+        assert_eq!(
+            SourceBlockEncodingPlan::deserialize_for(&serialized, NUM_SYMBOLS as u16 + 1),
+            Err(SourceBlockEncodingPlanError::UnexpectedSymbolCount {
+                expected: NUM_SYMBOLS as u16 + 1,
+                actual: NUM_SYMBOLS as u16
+            })
+        );
+
+        let data = gen_test_data(NUM_SYMBOLS as usize * SYMBOL_SIZE);
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 4, 1, 1, 4);
+        let loaded = SourceBlockEncodingPlan::deserialize(&serialized).unwrap();
+        assert_eq!(
+            SourceBlockEncoder::with_encoding_plan(0, &config, &data, &loaded),
+            SourceBlockEncoder::new(0, &config, &data)
+        );
+    }
+
+    #[test]
+    fn encoding_plan_rejects_corruption() {
+        let serialized = SourceBlockEncodingPlan::generate(NUM_SYMBOLS as u16).serialize();
+
+        let mut corrupted = serialized.clone();
+        let index = rand::rng().random_range(7..serialized.len());
+        corrupted[index] ^= 1;
+        assert!(SourceBlockEncodingPlan::deserialize(&corrupted).is_err());
+
+        let mut wrong_version = serialized.clone();
+        wrong_version[4] = 2;
+        assert_eq!(
+            SourceBlockEncodingPlan::deserialize(&wrong_version),
+            Err(SourceBlockEncodingPlanError::UnsupportedVersion(2))
+        );
+
+        assert_eq!(
+            SourceBlockEncodingPlan::deserialize(&serialized[..serialized.len() - 1]),
+            Err(SourceBlockEncodingPlanError::Truncated)
+        );
+        assert_eq!(
+            SourceBlockEncodingPlan::deserialize(b"nope"),
+            Err(SourceBlockEncodingPlanError::InvalidMagic)
+        );
+
+        // Checksummed correctly, but reordering two rows onto the same symbol
+        let intermediate_symbols = num_intermediate_symbols(NUM_SYMBOLS) as usize;
+        let mut order: Vec<usize> = (0..intermediate_symbols).collect();
+        order[1] = 0;
+        let aliased = SourceBlockEncodingPlan {
+            operations: vec![SymbolOps::Reorder { order }],
+            source_symbol_count: NUM_SYMBOLS as u16,
+        };
+        assert_eq!(
+            SourceBlockEncodingPlan::deserialize(&aliased.serialize()),
+            Err(SourceBlockEncodingPlanError::InvalidOperation)
+        );
+    }
+
+    #[test]
+    fn encoding_plan_library() {
+        let _lock = lock_global_cache_for_test();
+        let symbol_counts = [10, 11, 12];
+        let mut library = vec![];
+        write_encoding_plan_library(&symbol_counts, &mut library).unwrap();
+        assert_eq!(preload_encoding_plan_library(&library[..]).unwrap(), 3);
+        for symbol_count in symbol_counts {
+            assert_eq!(
+                *get_or_generate_plan(symbol_count),
+                SourceBlockEncodingPlan::generate(symbol_count)
+            );
+        }
+
+        library.truncate(library.len() - 1);
+        let error = preload_encoding_plan_library(&library[..]).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+
     #[test]
     fn unique_blocks() {
         let data = gen_test_data(120);
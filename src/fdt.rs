@@ -0,0 +1,226 @@
+#[cfg(feature = "std")]
+use std::{string::String, vec::Vec};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+use core::fmt;
+
+use crate::base::{ObjectTransmissionInformation, ObjectTransmissionInformationError};
+use crate::util::{base64_decode, base64_encode};
+
+/// FEC Encoding ID of RaptorQ, as registered in section 7.1.
+pub const RAPTORQ_FEC_ENCODING_ID: u8 = 6;
+
+// Attributes of the FDT File element, as defined in RFC 6726 section 3.4.2
+const TRANSFER_LENGTH_ATTRIBUTE: &str = "Transfer-Length";
+const CONTENT_LENGTH_ATTRIBUTE: &str = "Content-Length";
+const FEC_ENCODING_ID_ATTRIBUTE: &str = "FEC-OTI-FEC-Encoding-ID";
+const ENCODING_SYMBOL_LENGTH_ATTRIBUTE: &str = "FEC-OTI-Encoding-Symbol-Length";
+const SCHEME_SPECIFIC_INFO_ATTRIBUTE: &str = "FEC-OTI-Scheme-Specific-Info";
+
+/// Reason a set of FDT attributes does not describe a valid RaptorQ object.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FdtAttributeError {
+    /// A required attribute is absent.
+    MissingAttribute(&'static str),
+    /// The value of an attribute could not be parsed.
+    InvalidAttribute(&'static str),
+    /// The FEC Encoding ID identifies a scheme other than RaptorQ.
+    UnsupportedFecEncodingId(u8),
+    /// The parameters violate the constraints of section 4.4.1.2.
+    InvalidParameters(ObjectTransmissionInformationError),
+}
+
+impl fmt::Display for FdtAttributeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FdtAttributeError::MissingAttribute(name) => write!(f, "missing attribute {name}"),
+            FdtAttributeError::InvalidAttribute(name) => {
+                write!(f, "invalid value for attribute {name}")
+            }
+            FdtAttributeError::UnsupportedFecEncodingId(id) => {
+                write!(f, "unsupported FEC encoding ID {id}")
+            }
+            FdtAttributeError::InvalidParameters(err) => {
+                write!(f, "invalid object transmission information: {err}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FdtAttributeError {}
+
+impl ObjectTransmissionInformation {
+    /// Returns the attributes of an FDT File element (RFC 6726) which carry the FEC Object
+    /// Transmission Information, as name and value pairs. The Scheme-Specific element is base64
+    /// encoded.
+    pub fn fdt_attributes(&self) -> Vec<(&'static str, String)> {
+        vec![
+            (
+                TRANSFER_LENGTH_ATTRIBUTE,
+                format!("{}", self.transfer_length()),
+            ),
+            (
+                FEC_ENCODING_ID_ATTRIBUTE,
+                format!("{RAPTORQ_FEC_ENCODING_ID}"),
+            ),
+            (
+                ENCODING_SYMBOL_LENGTH_ATTRIBUTE,
+                format!("{}", self.symbol_size()),
+            ),
+            (
+                SCHEME_SPECIFIC_INFO_ATTRIBUTE,
+                base64_encode(&self.serialize_scheme_specific()),
+            ),
+        ]
+    }
+
+    /// Parses the FEC Object Transmission Information from the attributes of an FDT File
+    /// element, ignoring unrelated attributes. As specified by RFC 6726, `Content-Length` is used
+    /// if `Transfer-Length` is absent, and the FEC Encoding ID may be omitted if it is conveyed
+    /// elsewhere.
+    pub fn from_fdt_attributes<'a, I: IntoIterator<Item = (&'a str, &'a str)>>(
+        attributes: I,
+    ) -> Result<ObjectTransmissionInformation, FdtAttributeError> {
+        let mut transfer_length = None;
+        let mut content_length = None;
+        let mut encoding_id = None;
+        let mut symbol_size = None;
+        let mut scheme_specific = None;
+        for (name, value) in attributes {
+            let value = value.trim();
+            match name {
+                TRANSFER_LENGTH_ATTRIBUTE => {
+                    transfer_length = Some(parse_attribute(TRANSFER_LENGTH_ATTRIBUTE, value)?)
+                }
+                CONTENT_LENGTH_ATTRIBUTE => {
+                    content_length = Some(parse_attribute(CONTENT_LENGTH_ATTRIBUTE, value)?)
+                }
+                FEC_ENCODING_ID_ATTRIBUTE => {
+                    encoding_id = Some(parse_attribute(FEC_ENCODING_ID_ATTRIBUTE, value)?)
+                }
+                ENCODING_SYMBOL_LENGTH_ATTRIBUTE => {
+                    symbol_size = Some(parse_attribute(ENCODING_SYMBOL_LENGTH_ATTRIBUTE, value)?)
+                }
+                SCHEME_SPECIFIC_INFO_ATTRIBUTE => {
+                    let element: [u8; 4] = base64_decode(value)
+                        .and_then(|decoded| decoded.try_into().ok())
+                        .ok_or(FdtAttributeError::InvalidAttribute(
+                            SCHEME_SPECIFIC_INFO_ATTRIBUTE,
+                        ))?;
+                    scheme_specific = Some(element);
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(id) = encoding_id
+            && id != RAPTORQ_FEC_ENCODING_ID
+        {
+            return Err(FdtAttributeError::UnsupportedFecEncodingId(id));
+        }
+        let transfer_length: u64 =
+            transfer_length
+                .or(content_length)
+                .ok_or(FdtAttributeError::MissingAttribute(
+                    TRANSFER_LENGTH_ATTRIBUTE,
+                ))?;
+        let symbol_size: u16 = symbol_size.ok_or(FdtAttributeError::MissingAttribute(
+            ENCODING_SYMBOL_LENGTH_ATTRIBUTE,
+        ))?;
+        let scheme_specific = scheme_specific.ok_or(FdtAttributeError::MissingAttribute(
+            SCHEME_SPECIFIC_INFO_ATTRIBUTE,
+        ))?;
+
+        // Transfer lengths beyond 40 bits cannot be represented in the common element
+        if transfer_length >= 1 << 40 {
+            return Err(FdtAttributeError::InvalidParameters(
+                ObjectTransmissionInformationError::TransferLengthTooLarge(transfer_length),
+            ));
+        }
+        let mut common = [0; 8];
+        common[..5].copy_from_slice(&transfer_length.to_be_bytes()[3..]);
+        common[6..].copy_from_slice(&symbol_size.to_be_bytes());
+        ObjectTransmissionInformation::try_deserialize_elements(&common, &scheme_specific)
+            .map_err(FdtAttributeError::InvalidParameters)
+    }
+}
+
+fn parse_attribute<T: core::str::FromStr>(
+    name: &'static str,
+    value: &str,
+) -> Result<T, FdtAttributeError> {
+    value
+        .parse()
+        .map_err(|_| FdtAttributeError::InvalidAttribute(name))
+}
+
+#[cfg(feature = "std")]
+#[cfg(test)]
+mod tests {
+    use crate::{FdtAttributeError, ObjectTransmissionInformation};
+    use rand::Rng;
+
+    #[test]
+    fn fdt_attributes_round_trip() {
+        let oti = ObjectTransmissionInformation::with_defaults(
+            rand::rng().random_range(1..(1 << 30)),
+            rand::rng().random_range(1024..=u16::MAX),
+        );
+        let attributes = oti.fdt_attributes();
+        let parsed = ObjectTransmissionInformation::from_fdt_attributes(
+            attributes
+                .iter()
+                .map(|(name, value)| (*name, value.as_str())),
+        );
+        assert_eq!(parsed, Ok(oti));
+    }
+
+    #[test]
+    fn fdt_attributes() {
+        let oti = ObjectTransmissionInformation::new(1_000_000, 1280, 2, 1, 8);
+        assert_eq!(
+            oti.fdt_attributes(),
+            vec![
+                ("Transfer-Length", "1000000".to_string()),
+                ("FEC-OTI-FEC-Encoding-ID", "6".to_string()),
+                ("FEC-OTI-Encoding-Symbol-Length", "1280".to_string()),
+                // Z = 2, N = 1, Al = 8
+                ("FEC-OTI-Scheme-Specific-Info", "AgABCA==".to_string()),
+            ]
+        );
+
+        let attributes = [
+            ("Content-Location", "file:///object.bin"),
+            ("Content-Length", "1000000"),
+            ("FEC-OTI-Encoding-Symbol-Length", "1280"),
+            ("FEC-OTI-Scheme-Specific-Info", "AgABCA=="),
+        ];
+        assert_eq!(
+            ObjectTransmissionInformation::from_fdt_attributes(attributes),
+            Ok(oti)
+        );
+        assert_eq!(
+            ObjectTransmissionInformation::from_fdt_attributes(attributes[..3].iter().copied()),
+            Err(FdtAttributeError::MissingAttribute(
+                "FEC-OTI-Scheme-Specific-Info"
+            ))
+        );
+        assert_eq!(
+            ObjectTransmissionInformation::from_fdt_attributes([
+                ("Transfer-Length", "1000000"),
+                ("FEC-OTI-Encoding-Symbol-Length", "1280"),
+                ("FEC-OTI-Scheme-Specific-Info", "AgABCA="),
+            ]),
+            Err(FdtAttributeError::InvalidAttribute(
+                "FEC-OTI-Scheme-Specific-Info"
+            ))
+        );
+        assert_eq!(
+            ObjectTransmissionInformation::from_fdt_attributes([("FEC-OTI-FEC-Encoding-ID", "1")]),
+            Err(FdtAttributeError::UnsupportedFecEncodingId(1))
+        );
+    }
+}
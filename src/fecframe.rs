@@ -0,0 +1,671 @@
+use core::fmt;
+use std::collections::VecDeque;
+use std::vec::Vec;
+
+use crate::base::{EncodingPacket, ObjectTransmissionInformation, PayloadId};
+use crate::decoder::{DecodeError, SourceBlockDecoder};
+use crate::encoder::SourceBlockEncoder;
+use crate::systematic_constants::MAX_SOURCE_SYMBOLS_PER_BLOCK;
+
+// Flow ID and length fields which precede each ADU in the source block, RFC 6681 section 8.2.4
+const ADU_INFO_HEADER_BYTES: usize = 3;
+// 8 bit SBN and 16 bit ESI, RFC 6681 section 8.1.2
+const SOURCE_FEC_PAYLOAD_ID_BYTES: usize = 3;
+// Followed by the 16 bit source block length, RFC 6681 section 8.1.3
+const REPAIR_FEC_PAYLOAD_ID_BYTES: usize = 5;
+const DEFAULT_MAX_BLOCKS: usize = 16;
+
+/// Reason an ADU or a FECFRAME packet was rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FecFrameError {
+    /// The ADU is longer than 65535 bytes, or does not fit in a source block of the maximum
+    /// length.
+    AduTooLarge(usize),
+    /// The ADU does not fit in the remainder of the current source block, which must be finished
+    /// first.
+    SourceBlockFull,
+    /// The packet is shorter than its FEC Payload ID.
+    Truncated,
+    /// The FEC Payload ID is inconsistent with the source block length, or the symbol size.
+    InvalidPayloadId,
+    /// The decoder of the packet's source block rejected it.
+    Decode(DecodeError),
+    /// The FEC-Scheme-Specific Information has a zero symbol size, or a maximum source block
+    /// length which is zero or larger than RaptorQ supports.
+    InvalidConfig,
+}
+
+impl fmt::Display for FecFrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FecFrameError::AduTooLarge(length) => {
+                write!(f, "ADU of {length} bytes does not fit in a source block")
+            }
+            FecFrameError::SourceBlockFull => write!(f, "source block is full"),
+            FecFrameError::Truncated => write!(f, "packet is shorter than the FEC Payload ID"),
+            FecFrameError::InvalidPayloadId => write!(f, "invalid FEC Payload ID"),
+            FecFrameError::Decode(err) => write!(f, "packet rejected by decoder: {err}"),
+            FecFrameError::InvalidConfig => write!(f, "invalid FEC-Scheme-Specific Information"),
+        }
+    }
+}
+
+impl std::error::Error for FecFrameError {}
+
+/// FEC Framework Configuration Information of the RaptorQ scheme for arbitrary ADU flows,
+/// RFC 6681 section 8.1.1. Must be communicated to the receivers out of band, for example in SDP.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FecFrameConfig {
+    symbol_size: u16,
+    max_source_block_length: u16,
+}
+
+impl FecFrameConfig {
+    /// `max_source_block_length` is in symbols, and bounds both the decoding latency and the
+    /// number of ADUs protected together.
+    pub fn new(symbol_size: u16, max_source_block_length: u16) -> FecFrameConfig {
+        FecFrameConfig::try_new(symbol_size, max_source_block_length).unwrap()
+    }
+
+    /// Like `new`, but returns `FecFrameError::InvalidConfig` instead of panicking if the
+    /// parameters are out of range.
+    pub fn try_new(
+        symbol_size: u16,
+        max_source_block_length: u16,
+    ) -> Result<FecFrameConfig, FecFrameError> {
+        if symbol_size == 0
+            || max_source_block_length == 0
+            || max_source_block_length as u32 > MAX_SOURCE_SYMBOLS_PER_BLOCK
+        {
+            return Err(FecFrameError::InvalidConfig);
+        }
+        Ok(FecFrameConfig {
+            symbol_size,
+            max_source_block_length,
+        })
+    }
+
+    /// Parses the FEC-Scheme-Specific Information produced by `serialize`. Since it is received
+    /// from the network, invalid values are rejected with `FecFrameError::InvalidConfig`.
+    pub fn deserialize(data: &[u8; 4]) -> Result<FecFrameConfig, FecFrameError> {
+        FecFrameConfig::try_new(
+            u16::from_be_bytes([data[0], data[1]]),
+            u16::from_be_bytes([data[2], data[3]]),
+        )
+    }
+
+    /// Serializes the FEC-Scheme-Specific Information: the symbol size followed by the maximum
+    /// source block length.
+    pub fn serialize(&self) -> [u8; 4] {
+        let mut serialized = [0; 4];
+        serialized[..2].copy_from_slice(&self.symbol_size.to_be_bytes());
+        serialized[2..].copy_from_slice(&self.max_source_block_length.to_be_bytes());
+        serialized
+    }
+
+    pub fn symbol_size(&self) -> u16 {
+        self.symbol_size
+    }
+
+    pub fn max_source_block_length(&self) -> u16 {
+        self.max_source_block_length
+    }
+
+    // Configuration of a source block of a single sub-block
+    fn block_config(&self) -> ObjectTransmissionInformation {
+        ObjectTransmissionInformation::new(0, self.symbol_size, 1, 1, 1)
+    }
+
+    // Number of symbols occupied by an ADU and its ADU Information header
+    fn adu_symbols(&self, adu_len: usize) -> usize {
+        (ADU_INFO_HEADER_BYTES + adu_len).div_ceil(self.symbol_size as usize)
+    }
+}
+
+/// Repair FEC Payload ID, RFC 6681 section 8.1.3.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RepairFecPayloadId {
+    payload_id: PayloadId,
+    source_block_length: u16,
+}
+
+impl RepairFecPayloadId {
+    /// The encoding symbol ID of `payload_id` must fit in 16 bits.
+    pub fn new(payload_id: PayloadId, source_block_length: u16) -> RepairFecPayloadId {
+        assert!(payload_id.encoding_symbol_id() <= u16::MAX as u32);
+        RepairFecPayloadId {
+            payload_id,
+            source_block_length,
+        }
+    }
+
+    pub fn deserialize(data: &[u8; 5]) -> RepairFecPayloadId {
+        RepairFecPayloadId {
+            payload_id: deserialize_source_payload_id(&[data[0], data[1], data[2]]),
+            source_block_length: u16::from_be_bytes([data[3], data[4]]),
+        }
+    }
+
+    /// Serializes the 8 bit SBN and 16 bit ESI, followed by the source block length.
+    pub fn serialize(&self) -> [u8; 5] {
+        let mut serialized = [0; 5];
+        serialized[..3].copy_from_slice(&serialize_source_payload_id(&self.payload_id));
+        serialized[3..].copy_from_slice(&self.source_block_length.to_be_bytes());
+        serialized
+    }
+
+    pub fn payload_id(&self) -> &PayloadId {
+        &self.payload_id
+    }
+
+    /// Number of source symbols in the source block.
+    pub fn source_block_length(&self) -> u16 {
+        self.source_block_length
+    }
+}
+
+// The Source FEC Payload ID: an 8 bit SBN followed by a 16 bit ESI
+fn serialize_source_payload_id(payload_id: &PayloadId) -> [u8; SOURCE_FEC_PAYLOAD_ID_BYTES] {
+    let esi = (payload_id.encoding_symbol_id() as u16).to_be_bytes();
+    [payload_id.source_block_number(), esi[0], esi[1]]
+}
+
+fn deserialize_source_payload_id(data: &[u8; SOURCE_FEC_PAYLOAD_ID_BYTES]) -> PayloadId {
+    PayloadId::new(data[0], u16::from_be_bytes([data[1], data[2]]) as u32)
+}
+
+/// Splits a FEC source packet into the ADU and its Explicit Source FEC Payload ID, which is
+/// appended to the ADU.
+pub fn split_source_packet(packet: &[u8]) -> Result<(&[u8], PayloadId), FecFrameError> {
+    let adu_len = packet
+        .len()
+        .checked_sub(SOURCE_FEC_PAYLOAD_ID_BYTES)
+        .ok_or(FecFrameError::Truncated)?;
+    let (adu, payload_id) = packet.split_at(adu_len);
+    Ok((
+        adu,
+        deserialize_source_payload_id(payload_id.try_into().unwrap()),
+    ))
+}
+
+/// Protects a sequence of ADUs, from one or more flows, as described in RFC 6681 section 8.2.
+///
+/// Each ADU is added to the current source block, prefixed by its flow ID and length and padded
+/// to a whole number of symbols, and sent unmodified with its Explicit Source FEC Payload ID
+/// appended. Once the block is full, or the latency budget of the application is exhausted,
+/// `finish_block` produces the repair packets and starts a new block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FecFrameSender {
+    config: FecFrameConfig,
+    source_block_number: u8,
+    block: Vec<u8>,
+}
+
+impl FecFrameSender {
+    pub fn new(config: FecFrameConfig) -> FecFrameSender {
+        FecFrameSender {
+            config,
+            source_block_number: 0,
+            block: vec![],
+        }
+    }
+
+    pub fn get_config(&self) -> FecFrameConfig {
+        self.config
+    }
+
+    /// Source block number of the current block.
+    pub fn source_block_number(&self) -> u8 {
+        self.source_block_number
+    }
+
+    /// Number of source symbols in the current block.
+    pub fn source_block_length(&self) -> u16 {
+        (self.block.len() / self.config.symbol_size as usize) as u16
+    }
+
+    /// Adds `adu` of flow `flow_id` to the current source block, and returns the FEC source
+    /// packet to send.
+    pub fn add_adu(&mut self, flow_id: u8, adu: &[u8]) -> Result<Vec<u8>, FecFrameError> {
+        let symbols = self.config.adu_symbols(adu.len());
+        if adu.len() > u16::MAX as usize || symbols > self.config.max_source_block_length as usize {
+            return Err(FecFrameError::AduTooLarge(adu.len()));
+        }
+        let esi = self.source_block_length() as usize;
+        if esi + symbols > self.config.max_source_block_length as usize {
+            return Err(FecFrameError::SourceBlockFull);
+        }
+
+        self.block.push(flow_id);
+        self.block
+            .extend_from_slice(&(adu.len() as u16).to_be_bytes());
+        self.block.extend_from_slice(adu);
+        self.block
+            .resize((esi + symbols) * self.config.symbol_size as usize, 0);
+
+        let mut packet = Vec::with_capacity(adu.len() + SOURCE_FEC_PAYLOAD_ID_BYTES);
+        packet.extend_from_slice(adu);
+        packet.extend_from_slice(&serialize_source_payload_id(&PayloadId::new(
+            self.source_block_number,
+            esi as u32,
+        )));
+        Ok(packet)
+    }
+
+    /// Finishes the current source block, returning `repair_packets` FEC repair packets for it.
+    /// Returns no packets if the block is empty. Since the ESI is 16 bits, at most
+    /// 65536 - K repair packets can be produced for a block of K source symbols.
+    pub fn finish_block(&mut self, repair_packets: u32) -> Vec<Vec<u8>> {
+        if self.block.is_empty() {
+            return vec![];
+        }
+        let source_block_length = self.source_block_length();
+        let repair_packets = repair_packets.min(u16::MAX as u32 + 1 - source_block_length as u32);
+        let encoder = SourceBlockEncoder::new(
+            self.source_block_number,
+            &self.config.block_config(),
+            &self.block,
+        );
+        let packets = encoder
+            .repair_packets(0, repair_packets)
+            .into_iter()
+            .map(|packet| {
+                let (payload_id, symbol) = packet.split();
+                let mut serialized = Vec::with_capacity(REPAIR_FEC_PAYLOAD_ID_BYTES + symbol.len());
+                serialized.extend_from_slice(
+                    &RepairFecPayloadId::new(payload_id, source_block_length).serialize(),
+                );
+                serialized.extend_from_slice(&symbol);
+                serialized
+            })
+            .collect();
+
+        self.block.clear();
+        self.source_block_number = self.source_block_number.wrapping_add(1);
+        packets
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ReceivedBlock {
+    source_block_number: u8,
+    // ESIs of the first symbol of each ADU received in a source packet
+    received_adus: Vec<u32>,
+    // Symbols received before the source block length is known
+    pending: Vec<EncodingPacket>,
+    source_block_length: Option<u16>,
+    decoder: Option<SourceBlockDecoder>,
+    decoded: bool,
+}
+
+impl ReceivedBlock {
+    fn new(source_block_number: u8) -> ReceivedBlock {
+        ReceivedBlock {
+            source_block_number,
+            received_adus: vec![],
+            pending: vec![],
+            source_block_length: None,
+            decoder: None,
+            decoded: false,
+        }
+    }
+
+    // Adds symbols to the block, returning the source block once it has been decoded
+    fn add_symbols(
+        &mut self,
+        symbols: Vec<EncodingPacket>,
+    ) -> Result<Option<Vec<u8>>, FecFrameError> {
+        match self.decoder {
+            Some(ref mut decoder) => decoder.try_decode(symbols).map_err(FecFrameError::Decode),
+            None => {
+                self.pending.extend(symbols);
+                Ok(None)
+            }
+        }
+    }
+
+    fn add_source_symbols(
+        &mut self,
+        esi: u32,
+        symbols: Vec<EncodingPacket>,
+    ) -> Result<Option<Vec<u8>>, FecFrameError> {
+        if let Some(length) = self.source_block_length
+            && esi + symbols.len() as u32 > length as u32
+        {
+            return Err(FecFrameError::InvalidPayloadId);
+        }
+        let result = self.add_symbols(symbols)?;
+        self.received_adus.push(esi);
+        Ok(result)
+    }
+
+    fn add_repair_symbol(
+        &mut self,
+        config: &FecFrameConfig,
+        payload_id: &RepairFecPayloadId,
+        symbol: EncodingPacket,
+    ) -> Result<Option<Vec<u8>>, FecFrameError> {
+        let length = payload_id.source_block_length();
+        if length == 0
+            || length > config.max_source_block_length
+            || symbol.payload_id().encoding_symbol_id() < length as u32
+            || self
+                .source_block_length
+                .is_some_and(|known| known != length)
+        {
+            return Err(FecFrameError::InvalidPayloadId);
+        }
+        if self.decoder.is_none() {
+            // Discard the source symbols which do not fit in the block
+            let pending: Vec<EncodingPacket> = self
+                .pending
+                .drain(..)
+                .filter(|pending| pending.payload_id().encoding_symbol_id() < length as u32)
+                .collect();
+            self.received_adus.retain(|esi| *esi < length as u32);
+            self.source_block_length = Some(length);
+            self.decoder = Some(SourceBlockDecoder::new(
+                self.source_block_number,
+                &config.block_config(),
+                length as u64 * config.symbol_size as u64,
+            ));
+            if let Some(block) = self.add_symbols(pending)? {
+                return Ok(Some(block));
+            }
+        }
+        self.add_symbols(vec![symbol])
+    }
+
+    // Parses the ADUs of a decoded source block, returning those not received in source packets
+    fn missing_adus(&mut self, config: &FecFrameConfig, block: &[u8]) -> Vec<(u8, Vec<u8>)> {
+        self.decoded = true;
+        self.pending.clear();
+        self.decoder = None;
+
+        let symbol_size = config.symbol_size as usize;
+        let mut adus = vec![];
+        let mut esi = 0;
+        while esi * symbol_size + ADU_INFO_HEADER_BYTES <= block.len() {
+            let offset = esi * symbol_size;
+            let flow_id = block[offset];
+            let length = u16::from_be_bytes([block[offset + 1], block[offset + 2]]) as usize;
+            let start = offset + ADU_INFO_HEADER_BYTES;
+            // Only possible if the sender did not follow RFC 6681
+            let Some(adu) = block.get(start..start + length) else {
+                break;
+            };
+            if !self.received_adus.contains(&(esi as u32)) {
+                adus.push((flow_id, adu.to_vec()));
+            }
+            esi += config.adu_symbols(length);
+        }
+        adus
+    }
+}
+
+/// Receives the FEC source and repair packets produced by `FecFrameSender`, and recovers the
+/// ADUs whose source packets were lost.
+///
+/// ADUs received in source packets should be delivered to the application immediately, see
+/// `split_source_packet`, as only missing ADUs are returned by the receiver. An ADU whose source
+/// packet arrives after its block has been decoded is treated as missing, so the application
+/// must discard such duplicates, for example by RTP sequence number.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FecFrameReceiver {
+    config: FecFrameConfig,
+    blocks: VecDeque<ReceivedBlock>,
+    max_blocks: usize,
+}
+
+impl FecFrameReceiver {
+    pub fn new(config: FecFrameConfig) -> FecFrameReceiver {
+        FecFrameReceiver {
+            config,
+            blocks: VecDeque::new(),
+            max_blocks: DEFAULT_MAX_BLOCKS,
+        }
+    }
+
+    pub fn get_config(&self) -> FecFrameConfig {
+        self.config
+    }
+
+    /// Sets the number of source blocks which may be pending at once. When a packet of a new
+    /// block arrives, the least recently started block is discarded. Defaults to 16.
+    pub fn set_max_blocks(&mut self, max_blocks: usize) {
+        assert!(max_blocks > 0);
+        assert!(
+            max_blocks <= 128,
+            "source block numbers wrap after 256 blocks"
+        );
+        self.max_blocks = max_blocks;
+        while self.blocks.len() > max_blocks {
+            self.blocks.pop_front();
+        }
+    }
+
+    /// Processes a FEC source packet received on the flow identified by `flow_id`, returning the
+    /// ADUs recovered as a result, along with their flow IDs.
+    pub fn receive_source(
+        &mut self,
+        flow_id: u8,
+        packet: &[u8],
+    ) -> Result<Vec<(u8, Vec<u8>)>, FecFrameError> {
+        let (adu, payload_id) = split_source_packet(packet)?;
+        let symbols = self.config.adu_symbols(adu.len());
+        if adu.len() > u16::MAX as usize || symbols > self.config.max_source_block_length as usize {
+            return Err(FecFrameError::AduTooLarge(adu.len()));
+        }
+        let esi = payload_id.encoding_symbol_id();
+        if esi as usize + symbols > self.config.max_source_block_length as usize {
+            return Err(FecFrameError::InvalidPayloadId);
+        }
+
+        // Rebuild the symbols occupied by the ADU in the source block
+        let symbol_size = self.config.symbol_size as usize;
+        let mut adu_info = Vec::with_capacity(symbols * symbol_size);
+        adu_info.push(flow_id);
+        adu_info.extend_from_slice(&(adu.len() as u16).to_be_bytes());
+        adu_info.extend_from_slice(adu);
+        adu_info.resize(symbols * symbol_size, 0);
+        let sbn = payload_id.source_block_number();
+        let symbols = adu_info
+            .chunks(symbol_size)
+            .enumerate()
+            .map(|(i, symbol)| {
+                EncodingPacket::new(PayloadId::new(sbn, esi + i as u32), symbol.to_vec())
+            })
+            .collect();
+
+        let config = self.config;
+        let Some(block) = self.block(sbn) else {
+            return Ok(vec![]);
+        };
+        match block.add_source_symbols(esi, symbols)? {
+            Some(decoded) => Ok(block.missing_adus(&config, &decoded)),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Processes a FEC repair packet, returning the ADUs recovered as a result, along with their
+    /// flow IDs.
+    pub fn receive_repair(&mut self, packet: &[u8]) -> Result<Vec<(u8, Vec<u8>)>, FecFrameError> {
+        let payload_id: &[u8; REPAIR_FEC_PAYLOAD_ID_BYTES] = packet
+            .get(..REPAIR_FEC_PAYLOAD_ID_BYTES)
+            .and_then(|payload_id| payload_id.try_into().ok())
+            .ok_or(FecFrameError::Truncated)?;
+        let payload_id = RepairFecPayloadId::deserialize(payload_id);
+        let symbol = EncodingPacket::new(
+            payload_id.payload_id().clone(),
+            packet[REPAIR_FEC_PAYLOAD_ID_BYTES..].to_vec(),
+        );
+        if symbol.data().len() != self.config.symbol_size as usize {
+            return Err(FecFrameError::InvalidPayloadId);
+        }
+
+        let config = self.config;
+        let Some(block) = self.block(payload_id.payload_id().source_block_number()) else {
+            return Ok(vec![]);
+        };
+        match block.add_repair_symbol(&config, &payload_id, symbol)? {
+            Some(decoded) => Ok(block.missing_adus(&config, &decoded)),
+            None => Ok(vec![]),
+        }
+    }
+
+    // Returns the block with the given number, starting it if necessary, or None if it has
+    // already been decoded
+    fn block(&mut self, source_block_number: u8) -> Option<&mut ReceivedBlock> {
+        let index = match self
+            .blocks
+            .iter()
+            .position(|block| block.source_block_number == source_block_number)
+        {
+            Some(index) => index,
+            None => {
+                if self.blocks.len() == self.max_blocks {
+                    self.blocks.pop_front();
+                }
+                self.blocks
+                    .push_back(ReceivedBlock::new(source_block_number));
+                self.blocks.len() - 1
+            }
+        };
+        let block = &mut self.blocks[index];
+        if block.decoded { None } else { Some(block) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use rand::seq::SliceRandom;
+    use std::vec::Vec;
+
+    use crate::{
+        FecFrameConfig, FecFrameError, FecFrameReceiver, FecFrameSender, PayloadId,
+        RepairFecPayloadId, split_source_packet,
+    };
+
+    #[test]
+    fn payload_id_serialization() {
+        let config = FecFrameConfig::new(1280, 100);
+        assert_eq!(FecFrameConfig::deserialize(&config.serialize()), Ok(config));
+        assert_eq!(
+            FecFrameConfig::deserialize(&[0, 0, 0, 100]),
+            Err(FecFrameError::InvalidConfig)
+        );
+        assert_eq!(
+            FecFrameConfig::deserialize(&[5, 0, 0, 0]),
+            Err(FecFrameError::InvalidConfig)
+        );
+        assert_eq!(
+            FecFrameConfig::deserialize(&[5, 0, 0xFF, 0xFF]),
+            Err(FecFrameError::InvalidConfig)
+        );
+
+        let payload_id = RepairFecPayloadId::new(PayloadId::new(7, 1000), 42);
+        assert_eq!(payload_id.serialize(), [7, 0x03, 0xE8, 0, 42]);
+        assert_eq!(
+            RepairFecPayloadId::deserialize(&payload_id.serialize()),
+            payload_id
+        );
+
+        let (adu, payload_id) = split_source_packet(&[1, 2, 3, 9, 0x01, 0x05]).unwrap();
+        assert_eq!(adu, &[1, 2, 3]);
+        assert_eq!(payload_id, PayloadId::new(9, 0x105));
+        assert_eq!(split_source_packet(&[1, 2]), Err(FecFrameError::Truncated));
+    }
+
+    #[test]
+    fn source_block_limits() {
+        let mut sender = FecFrameSender::new(FecFrameConfig::new(16, 4));
+        assert_eq!(
+            sender.add_adu(0, &[0; 62]),
+            Err(FecFrameError::AduTooLarge(62))
+        );
+        // 3 symbols, including the ADU Information header
+        let packet = sender.add_adu(0, &[1; 40]).unwrap();
+        assert_eq!(packet.len(), 43);
+        assert_eq!(
+            split_source_packet(&packet).unwrap().1,
+            PayloadId::new(0, 0)
+        );
+        assert_eq!(sender.source_block_length(), 3);
+        assert_eq!(
+            sender.add_adu(1, &[2; 14]),
+            Err(FecFrameError::SourceBlockFull)
+        );
+        let packet = sender.add_adu(1, &[2; 13]).unwrap();
+        assert_eq!(
+            split_source_packet(&packet).unwrap().1,
+            PayloadId::new(0, 3)
+        );
+
+        assert_eq!(sender.finish_block(2).len(), 2);
+        assert_eq!(sender.source_block_number(), 1);
+        assert_eq!(sender.source_block_length(), 0);
+        assert!(sender.finish_block(2).is_empty());
+    }
+
+    #[test]
+    fn recover_missing_adus() {
+        let config = FecFrameConfig::new(64, 40);
+        let mut sender = FecFrameSender::new(config);
+        let mut receiver = FecFrameReceiver::new(config);
+
+        let mut expected = vec![];
+        let mut recovered = vec![];
+        for _ in 0..10 {
+            let mut source_packets = vec![];
+            let mut adus = vec![];
+            loop {
+                let flow_id = rand::rng().random_range(0..3);
+                let length = rand::rng().random_range(0..200);
+                let adu: Vec<u8> = (0..length).map(|_| rand::rng().random()).collect();
+                match sender.add_adu(flow_id, &adu) {
+                    Ok(packet) => {
+                        source_packets.push((flow_id, packet));
+                        adus.push((flow_id, adu));
+                    }
+                    Err(FecFrameError::SourceBlockFull) => break,
+                    Err(err) => panic!("{err}"),
+                }
+            }
+            let source_symbols = sender.source_block_length() as u32;
+            let repair_packets = sender.finish_block(source_symbols);
+
+            // Drop ADUs, reorder the remaining source packets, and follow them with enough
+            // repair packets to replace the dropped ADUs
+            let mut lost_symbols = 0;
+            let mut packets = vec![];
+            for (i, (flow_id, packet)) in source_packets.into_iter().enumerate() {
+                if rand::rng().random_bool(0.3) {
+                    lost_symbols += config.adu_symbols(packet.len() - 3);
+                    expected.push(adus[i].clone());
+                } else {
+                    packets.push((Some(flow_id), packet));
+                }
+            }
+            packets.shuffle(&mut rand::rng());
+            packets.extend(
+                repair_packets
+                    .into_iter()
+                    .take(lost_symbols + 2)
+                    .map(|packet| (None, packet)),
+            );
+
+            for (flow_id, packet) in packets {
+                let adus = match flow_id {
+                    Some(flow_id) => receiver.receive_source(flow_id, &packet),
+                    None => receiver.receive_repair(&packet),
+                };
+                recovered.extend(adus.unwrap());
+            }
+        }
+
+        expected.sort();
+        recovered.sort();
+        assert_eq!(recovered, expected);
+    }
+}
@@ -16,11 +16,19 @@ extern crate core;
 #[macro_use]
 extern crate std;
 
+#[cfg(feature = "std")]
+mod alc;
 mod arraymap;
 mod base;
+mod checksum;
 mod constraint_matrix;
+#[cfg(feature = "std")]
+mod container;
 mod decoder;
 mod encoder;
+mod fdt;
+#[cfg(feature = "std")]
+mod fecframe;
 mod gf2;
 mod graph;
 mod iterators;
@@ -29,37 +37,128 @@ mod octet;
 mod octet_matrix;
 mod octets;
 mod operation_vector;
+mod packet_auth;
+#[cfg(feature = "parallel")]
+mod parallel_decoder;
 mod pi_solver;
+#[cfg(feature = "std")]
+mod plan_cache;
 #[cfg(feature = "python")]
 mod python;
+mod r10;
 mod rng;
 mod sparse_matrix;
 mod sparse_vec;
+#[cfg(feature = "std")]
+mod streaming;
 mod symbol;
 mod symbol_slab;
 mod systematic_constants;
 mod util;
 
+#[cfg(feature = "std")]
+pub use crate::alc::AlcError;
+#[cfg(feature = "std")]
+pub use crate::alc::AlcPacket;
+#[cfg(feature = "std")]
+pub use crate::alc::AlcReceiver;
+#[cfg(feature = "std")]
+pub use crate::alc::AlcSender;
+#[cfg(feature = "std")]
+pub use crate::alc::EXT_FTI;
+#[cfg(feature = "std")]
+pub use crate::alc::EXT_NOP;
+#[cfg(feature = "std")]
+pub use crate::alc::HeaderExtension;
+#[cfg(feature = "std")]
+pub use crate::alc::LctHeader;
 pub use crate::base::EncodingPacket;
+pub use crate::base::EncodingPacketRef;
 pub use crate::base::ObjectTransmissionInformation;
+pub use crate::base::ObjectTransmissionInformationError;
 pub use crate::base::PayloadId;
 pub use crate::base::partition;
-#[cfg(not(feature = "python"))]
+pub use crate::checksum::CRC32C_ALGORITHM;
+pub use crate::checksum::Crc32c;
+pub use crate::checksum::ObjectChecksum;
+pub use crate::checksum::ObjectHasher;
+#[cfg(feature = "std")]
+pub use crate::container::ContainerError;
+#[cfg(feature = "std")]
+pub use crate::container::ContainerReader;
+#[cfg(feature = "std")]
+pub use crate::container::ContainerWriter;
+pub use crate::decoder::DecodeError;
 pub use crate::decoder::Decoder;
 pub use crate::decoder::SourceBlockDecoder;
-#[cfg(not(feature = "python"))]
+pub use crate::decoder::SourceBlockDecodingPlan;
+pub use crate::encoder::EncodedPacketIter;
 pub use crate::encoder::Encoder;
 pub use crate::encoder::EncoderBuilder;
+pub use crate::encoder::RepairPacketIter;
 pub use crate::encoder::SourceBlockEncoder;
 pub use crate::encoder::SourceBlockEncodingPlan;
+pub use crate::encoder::SourceBlockEncodingPlanError;
 pub use crate::encoder::calculate_block_offsets;
+#[cfg(feature = "std")]
+pub use crate::encoder::preload_encoding_plan_library;
+#[cfg(feature = "std")]
+pub use crate::encoder::write_encoding_plan_library;
+pub use crate::fdt::FdtAttributeError;
+pub use crate::fdt::RAPTORQ_FEC_ENCODING_ID;
+#[cfg(feature = "std")]
+pub use crate::fecframe::FecFrameConfig;
+#[cfg(feature = "std")]
+pub use crate::fecframe::FecFrameError;
+#[cfg(feature = "std")]
+pub use crate::fecframe::FecFrameReceiver;
+#[cfg(feature = "std")]
+pub use crate::fecframe::FecFrameSender;
+#[cfg(feature = "std")]
+pub use crate::fecframe::RepairFecPayloadId;
+#[cfg(feature = "std")]
+pub use crate::fecframe::split_source_packet;
+#[cfg(feature = "hmac_sha256")]
+pub use crate::packet_auth::HMAC_SHA256_TRAILER_LEN;
+#[cfg(feature = "hmac_sha256")]
+pub use crate::packet_auth::HmacSha256;
+pub use crate::packet_auth::PacketSigner;
+pub use crate::packet_auth::PacketVerifier;
+#[cfg(feature = "parallel")]
+pub use crate::parallel_decoder::ParallelDecoder;
+#[cfg(feature = "std")]
+pub use crate::plan_cache::EncodingPlanCache;
+#[cfg(feature = "std")]
+pub use crate::plan_cache::EncodingPlanCacheStats;
+#[cfg(feature = "std")]
+pub use crate::plan_cache::LruEncodingPlanCache;
+#[cfg(feature = "std")]
+pub use crate::plan_cache::encoding_plan_cache;
+#[cfg(feature = "std")]
+pub use crate::plan_cache::set_encoding_plan_cache;
 #[cfg(feature = "python")]
-pub use crate::python::Decoder;
+pub use crate::python::PyDecoder;
 #[cfg(feature = "python")]
-pub use crate::python::Encoder;
+pub use crate::python::PyEncoder;
 #[cfg(feature = "python")]
 pub use crate::python::raptorq;
+pub use crate::r10::R10_MAX_SOURCE_SYMBOLS_PER_BLOCK;
+pub use crate::r10::R10_MIN_SOURCE_SYMBOLS_PER_BLOCK;
+pub use crate::r10::R10Decoder;
+pub use crate::r10::R10Encoder;
+pub use crate::r10::R10EncodingPacket;
+pub use crate::r10::R10Error;
+pub use crate::r10::R10ObjectTransmissionInformation;
+pub use crate::r10::R10PayloadId;
+pub use crate::r10::RAPTOR_FEC_ENCODING_ID;
+#[cfg(feature = "std")]
+pub use crate::streaming::StreamingDecoder;
+#[cfg(feature = "std")]
+pub use crate::streaming::StreamingEncoder;
 pub use crate::systematic_constants::extended_source_block_symbols;
+pub use crate::systematic_constants::num_hdpc_symbols;
+pub use crate::systematic_constants::num_intermediate_symbols;
+pub use crate::systematic_constants::num_ldpc_symbols;
 
 #[cfg(feature = "benchmarking")]
 pub use crate::constraint_matrix::generate_constraint_matrix;
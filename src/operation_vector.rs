@@ -1,10 +1,14 @@
 #[cfg(feature = "std")]
 use std::vec::Vec;
+#[cfg(feature = "parallel")]
+use std::{cell::Cell, thread};
 
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 
 use crate::octet::Octet;
+#[cfg(feature = "parallel")]
+use crate::symbol_slab::ColumnsMut;
 use crate::symbol_slab::SymbolSlab;
 #[cfg(feature = "serde_support")]
 use serde::{Deserialize, Serialize};
@@ -48,6 +52,105 @@ pub fn perform_op(op: &SymbolOps, symbols: &mut SymbolSlab) {
     }
 }
 
+// Symbols are only split into stripes of at least this many bytes, so that the work on each
+// thread outweighs the cost of spawning it
+#[cfg(feature = "parallel")]
+const MIN_STRIPE_BYTES: usize = 4096;
+
+// Stripe boundaries are kept aligned to this many bytes, for the benefit of the SIMD kernels
+#[cfg(feature = "parallel")]
+const STRIPE_ALIGNMENT: usize = 64;
+
+#[cfg(feature = "parallel")]
+thread_local! {
+    // Number of threads perform_ops may split symbols across on the current thread. It is only
+    // raised for the duration of with_column_threads(), and threads spawned in the meantime start
+    // from 1, so work which is already running on a worker thread is never striped again
+    static COLUMN_THREADS: Cell<usize> = const { Cell::new(1) };
+}
+
+// Runs `f` with column striping of perform_ops enabled on the current thread, using up to
+// `threads` threads. Values of 0 and 1 disable striping
+#[cfg(feature = "parallel")]
+pub fn with_column_threads<R>(threads: usize, f: impl FnOnce() -> R) -> R {
+    struct Restore(usize);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            COLUMN_THREADS.set(self.0);
+        }
+    }
+
+    let _restore = Restore(COLUMN_THREADS.replace(threads.max(1)));
+    f()
+}
+
+#[cfg(not(feature = "parallel"))]
+pub fn with_column_threads<R>(_threads: usize, f: impl FnOnce() -> R) -> R {
+    f()
+}
+
+// Applies all the operations, in order. Inside with_column_threads(), large symbols are split
+// column-wise into stripes, and the operations are replayed on each stripe in its own thread.
+pub fn perform_ops(ops: &[SymbolOps], symbols: &mut SymbolSlab) {
+    #[cfg(feature = "parallel")]
+    {
+        let stripes = COLUMN_THREADS
+            .get()
+            .min(symbols.symbol_size() / MIN_STRIPE_BYTES);
+        if stripes > 1 {
+            perform_ops_striped(ops, symbols, stripes);
+            return;
+        }
+    }
+
+    for op in ops {
+        perform_op(op, symbols);
+    }
+}
+
+#[cfg(feature = "parallel")]
+fn perform_ops_striped(ops: &[SymbolOps], symbols: &mut SymbolSlab, stripes: usize) {
+    let symbol_size = symbols.symbol_size();
+    let stripe_size = symbol_size
+        .div_ceil(stripes)
+        .next_multiple_of(STRIPE_ALIGNMENT);
+    let ranges: Vec<(usize, usize)> = (0..symbol_size)
+        .step_by(stripe_size)
+        .map(|start| (start, (start + stripe_size).min(symbol_size)))
+        .collect();
+
+    let mut views = symbols.column_views_mut(&ranges);
+    thread::scope(|scope| {
+        for view in views.iter_mut() {
+            scope.spawn(move || {
+                for op in ops {
+                    perform_op_on_columns(op, view);
+                }
+            });
+        }
+    });
+    let mapping = views.swap_remove(0).into_mapping();
+    symbols.set_mapping(mapping);
+}
+
+#[cfg(feature = "parallel")]
+fn perform_op_on_columns(op: &SymbolOps, columns: &mut ColumnsMut) {
+    match op {
+        SymbolOps::AddAssign { dest, src } => {
+            columns.add_assign(*dest, *src);
+        }
+        SymbolOps::MulAssign { dest, scalar } => {
+            columns.mulassign_scalar(*dest, scalar);
+        }
+        SymbolOps::FMA { dest, src, scalar } => {
+            columns.fma(*dest, *src, scalar);
+        }
+        SymbolOps::Reorder { order } => {
+            columns.set_reorder(order.clone());
+        }
+    }
+}
+
 #[cfg(feature = "std")]
 #[cfg(test)]
 mod tests {
@@ -55,6 +158,10 @@ mod tests {
     use std::vec::Vec;
 
     use crate::octet::Octet;
+    #[cfg(feature = "parallel")]
+    use crate::operation_vector::{
+        COLUMN_THREADS, perform_ops, perform_ops_striped, with_column_threads,
+    };
     use crate::operation_vector::{SymbolOps, perform_op};
     use crate::symbol::Symbol;
     use crate::symbol_slab::SymbolSlab;
@@ -164,4 +271,60 @@ mod tests {
         assert_eq!(slab.get(8)[0], 2);
         assert_eq!(slab.get(9)[0], 4);
     }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_striped_matches_serial() {
+        let rows = 20;
+        let symbol_size = 10_000;
+        let symbols: Vec<Symbol> = (0..rows)
+            .map(|_| Symbol::new((0..symbol_size).map(|_| rand::rng().random()).collect()))
+            .collect();
+        let mut ops = vec![];
+        for _ in 0..200 {
+            let dest = rand::rng().random_range(0..rows);
+            let src = (dest + rand::rng().random_range(1..rows)) % rows;
+            let scalar = Octet::new(rand::rng().random_range(2..=255));
+            ops.push(match rand::rng().random_range(0..3) {
+                0 => SymbolOps::AddAssign { dest, src },
+                1 => SymbolOps::MulAssign { dest, scalar },
+                _ => SymbolOps::FMA { dest, src, scalar },
+            });
+        }
+        ops.push(SymbolOps::Reorder {
+            order: (0..rows).rev().collect(),
+        });
+
+        let mut expected = SymbolSlab::from_symbols(symbols.clone(), symbol_size);
+        for op in ops.iter() {
+            perform_op(op, &mut expected);
+        }
+
+        for stripes in [2, 3, 7] {
+            let mut slab = SymbolSlab::from_symbols(symbols.clone(), symbol_size);
+            perform_ops_striped(&ops, &mut slab, stripes);
+            for i in 0..rows {
+                assert_eq!(expected.get(i), slab.get(i));
+            }
+        }
+
+        let mut slab = SymbolSlab::from_symbols(symbols, symbol_size);
+        with_column_threads(4, || perform_ops(&ops, &mut slab));
+        for i in 0..rows {
+            assert_eq!(expected.get(i), slab.get(i));
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn column_threads_scoped_to_caller() {
+        assert_eq!(COLUMN_THREADS.get(), 1);
+        with_column_threads(4, || {
+            assert_eq!(COLUMN_THREADS.get(), 4);
+            std::thread::spawn(|| assert_eq!(COLUMN_THREADS.get(), 1))
+                .join()
+                .unwrap();
+        });
+        assert_eq!(COLUMN_THREADS.get(), 1);
+    }
 }
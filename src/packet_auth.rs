@@ -0,0 +1,130 @@
+#[cfg(feature = "std")]
+use std::{fmt, sync::Arc, vec::Vec};
+
+#[cfg(not(feature = "std"))]
+use alloc::{sync::Arc, vec::Vec};
+#[cfg(not(feature = "std"))]
+use core::fmt;
+
+use crate::base::EncodingPacketRef;
+#[cfg(feature = "hmac_sha256")]
+use hmac::{Hmac, Mac};
+#[cfg(feature = "hmac_sha256")]
+use sha2::Sha256;
+
+/// Computes the authentication trailer, such as an HMAC or signature, which the sender appends
+/// to each serialized packet. See `EncodingPacket::serialize_signed`.
+pub trait PacketSigner {
+    /// Returns the trailer for `packet`, a serialized `EncodingPacket`.
+    fn sign(&self, packet: &[u8]) -> Vec<u8>;
+}
+
+/// Checks the authentication trailer of each serialized packet before the decoder accepts it.
+/// See `Decoder::set_packet_verifier`.
+pub trait PacketVerifier: Send + Sync {
+    /// Length of the trailer in bytes.
+    fn trailer_len(&self) -> usize;
+
+    /// Returns whether `trailer` authenticates `packet`, the serialized `EncodingPacket` which
+    /// precedes it.
+    fn verify(&self, packet: &[u8], trailer: &[u8]) -> bool;
+}
+
+// Splits a signed packet into the packet and its trailer, and returns the packet if the trailer
+// authenticates it
+pub(crate) fn verify_signed_packet<'a>(
+    verifier: &dyn PacketVerifier,
+    data: &'a [u8],
+) -> Option<EncodingPacketRef<'a>> {
+    let packet_len = data.len().checked_sub(verifier.trailer_len())?;
+    let (packet, trailer) = data.split_at(packet_len);
+    // A packet has at least a payload ID
+    let parsed = EncodingPacketRef::try_deserialize(packet).ok()?;
+    if !verifier.verify(packet, trailer) {
+        return None;
+    }
+    Some(parsed)
+}
+
+// Lets Decoder keep deriving Clone, Debug, PartialEq and Eq while holding a verifier
+#[derive(Clone)]
+pub(crate) struct SharedPacketVerifier(pub(crate) Arc<dyn PacketVerifier>);
+
+impl fmt::Debug for SharedPacketVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PacketVerifier")
+    }
+}
+
+impl PartialEq for SharedPacketVerifier {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for SharedPacketVerifier {}
+
+/// Length in bytes of the trailer produced by `HmacSha256`.
+#[cfg(feature = "hmac_sha256")]
+pub const HMAC_SHA256_TRAILER_LEN: usize = 32;
+
+/// HMAC-SHA256 (RFC 2104) of the serialized packet, keyed with a secret shared by the sender and
+/// the receivers. Implements both `PacketSigner` and `PacketVerifier`.
+#[cfg(feature = "hmac_sha256")]
+#[derive(Clone)]
+pub struct HmacSha256 {
+    mac: Hmac<Sha256>,
+}
+
+#[cfg(feature = "hmac_sha256")]
+impl HmacSha256 {
+    pub fn new(key: &[u8]) -> HmacSha256 {
+        HmacSha256 {
+            // HMAC accepts keys of any length
+            mac: Hmac::new_from_slice(key).unwrap(),
+        }
+    }
+}
+
+#[cfg(feature = "hmac_sha256")]
+impl PacketSigner for HmacSha256 {
+    fn sign(&self, packet: &[u8]) -> Vec<u8> {
+        let mut mac = self.mac.clone();
+        mac.update(packet);
+        mac.finalize().into_bytes().to_vec()
+    }
+}
+
+#[cfg(feature = "hmac_sha256")]
+impl PacketVerifier for HmacSha256 {
+    fn trailer_len(&self) -> usize {
+        HMAC_SHA256_TRAILER_LEN
+    }
+
+    fn verify(&self, packet: &[u8], trailer: &[u8]) -> bool {
+        let mut mac = self.mac.clone();
+        mac.update(packet);
+        // Constant time comparison
+        mac.verify_slice(trailer).is_ok()
+    }
+}
+
+#[cfg(feature = "hmac_sha256")]
+#[cfg(test)]
+mod tests {
+    use crate::{HmacSha256, PacketSigner};
+
+    #[test]
+    fn hmac_sha256_test_vector() {
+        // RFC 4231, test case 2
+        let signer = HmacSha256::new(b"Jefe");
+        assert_eq!(
+            signer.sign(b"what do ya want for nothing?"),
+            [
+                0x5b, 0xdc, 0xc1, 0x46, 0xbf, 0x60, 0x75, 0x4e, 0x6a, 0x04, 0x24, 0x26, 0x08, 0x95,
+                0x75, 0xc7, 0x5a, 0x00, 0x3f, 0x08, 0x9d, 0x27, 0x39, 0x83, 0x9d, 0xec, 0x58, 0xb9,
+                0x64, 0xec, 0x38, 0x43
+            ]
+        );
+    }
+}
@@ -0,0 +1,352 @@
+use std::collections::VecDeque;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::thread;
+use std::vec::Vec;
+
+use crate::base::{EncodingPacket, ObjectTransmissionInformation};
+use crate::decoder::{
+    DecodeError, SourceBlockDecoder, create_block_decoders, validate_block_packet,
+};
+
+enum BlockState {
+    Receiving(SourceBlockDecoder),
+    // The decoder has been moved to a worker thread
+    Solving,
+    Decoded,
+    // The solver panicked, leaving the decoder in an unknown state
+    Failed,
+}
+
+// The solve result is an error if the solver panicked
+type SolveResult = (usize, SourceBlockDecoder, thread::Result<Option<Vec<u8>>>);
+
+type CompletionCallback = Arc<dyn Fn(u8) + Send + Sync>;
+
+/// Decoder which solves independent source blocks on worker threads.
+///
+/// Packets are added on the calling thread, and as soon as a block has received enough symbols to
+/// attempt decoding, it is handed off to a worker. Packets for a block that arrive while it is
+/// being solved are buffered, and used if the attempt fails.
+pub struct ParallelDecoder {
+    config: ObjectTransmissionInformation,
+    states: Vec<BlockState>,
+    // Packets received for each block while it was being solved
+    buffered_packets: Vec<Vec<EncodingPacket>>,
+    // Number of symbols each block had received at its last failed decoding attempt
+    attempted_symbols: Vec<u32>,
+    blocks: Vec<Option<Vec<u8>>>,
+    ready_blocks: VecDeque<usize>,
+    completed_blocks: Vec<u8>,
+    max_threads: usize,
+    in_flight: usize,
+    sender: Sender<SolveResult>,
+    receiver: Receiver<SolveResult>,
+    completion_callback: Option<CompletionCallback>,
+    #[cfg(test)]
+    panic_in_solver: bool,
+}
+
+impl ParallelDecoder {
+    /// Creates a decoder which runs up to one solve per CPU concurrently.
+    pub fn new(config: ObjectTransmissionInformation) -> ParallelDecoder {
+        let threads = thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        ParallelDecoder::with_threads(config, threads)
+    }
+
+    /// Creates a decoder which runs up to `max_threads` solves concurrently.
+    pub fn with_threads(
+        config: ObjectTransmissionInformation,
+        max_threads: usize,
+    ) -> ParallelDecoder {
+        assert!(max_threads > 0);
+        let states: Vec<BlockState> = create_block_decoders(&config)
+            .into_iter()
+            .map(BlockState::Receiving)
+            .collect();
+        let blocks = states.len();
+        let (sender, receiver) = channel();
+        ParallelDecoder {
+            config,
+            states,
+            buffered_packets: vec![vec![]; blocks],
+            attempted_symbols: vec![0; blocks],
+            blocks: vec![None; blocks],
+            ready_blocks: VecDeque::new(),
+            completed_blocks: vec![],
+            max_threads,
+            in_flight: 0,
+            sender,
+            receiver,
+            completion_callback: None,
+            #[cfg(test)]
+            panic_in_solver: false,
+        }
+    }
+
+    pub fn get_config(&self) -> ObjectTransmissionInformation {
+        self.config
+    }
+
+    /// Calls `callback` with the number of each source block as soon as it has been decoded, on
+    /// the worker thread which decoded it, for example to wake up a thread waiting on `join`.
+    ///
+    /// The callback applies to solves started after it is set, so it should be set before any
+    /// packets are added. Blocks are still reported by `poll_completed` too.
+    pub fn set_completion_callback<F: Fn(u8) + Send + Sync + 'static>(&mut self, callback: F) {
+        self.completion_callback = Some(Arc::new(callback));
+    }
+
+    /// Adds a packet, dispatching its block to a worker thread if it may now be decodable.
+    ///
+    /// Returns `DecodeError::AlreadyDecoded` for packets belonging to a source block which has
+    /// already been recovered. Such packets are harmless and may simply be dropped.
+    /// `DecodeError::SolverFailed` is returned for packets of a block whose solver panicked.
+    pub fn add_new_packet(&mut self, packet: EncodingPacket) -> Result<(), DecodeError> {
+        self.process_finished_solves();
+
+        let source_block_number = packet.payload_id().source_block_number();
+        let block_number = source_block_number as usize;
+        match self.states.get_mut(block_number) {
+            None => return Err(DecodeError::InvalidSourceBlockNumber(source_block_number)),
+            Some(BlockState::Decoded) => return Err(DecodeError::AlreadyDecoded),
+            Some(BlockState::Failed) => {
+                return Err(DecodeError::SolverFailed(source_block_number));
+            }
+            Some(BlockState::Solving) => {
+                validate_block_packet(
+                    source_block_number,
+                    self.config.symbol_size(),
+                    packet.payload_id(),
+                    packet.data(),
+                )?;
+                self.buffered_packets[block_number].push(packet);
+            }
+            Some(BlockState::Receiving(decoder)) => {
+                decoder.validate_packet(packet.payload_id(), packet.data())?;
+                let (payload_id, payload) = packet.split();
+                decoder.add_symbol(payload_id, payload);
+                self.mark_if_ready(block_number);
+            }
+        }
+        self.dispatch();
+        Ok(())
+    }
+
+    /// Returns the numbers of the source blocks which have been decoded since the last call.
+    pub fn poll_completed(&mut self) -> Vec<u8> {
+        self.process_finished_solves();
+        mem::take(&mut self.completed_blocks)
+    }
+
+    /// Returns true once every source block has been decoded.
+    pub fn is_complete(&mut self) -> bool {
+        self.process_finished_solves();
+        self.blocks.iter().all(|block| block.is_some())
+    }
+
+    /// Waits for all in-progress solves to finish, and returns the object if every source block
+    /// has been decoded.
+    ///
+    /// Returns `DecodeError::SolverFailed` if the solver panicked for any block.
+    pub fn join(&mut self) -> Result<Option<Vec<u8>>, DecodeError> {
+        while self.in_flight > 0 {
+            // Every worker sends a result, even if its solver panics, and the sender is owned by
+            // self, so this cannot fail
+            let result = self.receiver.recv().unwrap();
+            self.finish_solve(result);
+            self.dispatch();
+        }
+
+        if let Some(block_number) = self
+            .states
+            .iter()
+            .position(|state| matches!(state, BlockState::Failed))
+        {
+            return Err(DecodeError::SolverFailed(block_number as u8));
+        }
+        let mut result = vec![];
+        for block in self.blocks.iter() {
+            let Some(block) = block else {
+                return Ok(None);
+            };
+            result.extend(block);
+        }
+        result.truncate(self.config.transfer_length() as usize);
+        Ok(Some(result))
+    }
+
+    fn mark_if_ready(&mut self, block_number: usize) {
+        if let BlockState::Receiving(decoder) = &self.states[block_number] {
+            let received = decoder.received_symbols();
+            if received >= decoder.source_block_symbols()
+                && received > self.attempted_symbols[block_number]
+                && !self.ready_blocks.contains(&block_number)
+            {
+                self.ready_blocks.push_back(block_number);
+            }
+        }
+    }
+
+    fn dispatch(&mut self) {
+        while self.in_flight < self.max_threads {
+            let Some(block_number) = self.ready_blocks.pop_front() else {
+                return;
+            };
+            let state = mem::replace(&mut self.states[block_number], BlockState::Solving);
+            let BlockState::Receiving(mut decoder) = state else {
+                unreachable!();
+            };
+            let sender = self.sender.clone();
+            let callback = self.completion_callback.clone();
+            #[cfg(test)]
+            let panic_in_solver = self.panic_in_solver;
+            thread::spawn(move || {
+                let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                    #[cfg(test)]
+                    assert!(!panic_in_solver, "injected solver panic");
+                    decoder.try_complete()
+                }));
+                if let (Ok(Some(_)), Some(callback)) = (&result, callback) {
+                    callback(block_number as u8);
+                }
+                // The receiver is only dropped along with the ParallelDecoder
+                let _ = sender.send((block_number, decoder, result));
+            });
+            self.in_flight += 1;
+        }
+    }
+
+    fn process_finished_solves(&mut self) {
+        while let Ok(result) = self.receiver.try_recv() {
+            self.finish_solve(result);
+        }
+        self.dispatch();
+    }
+
+    fn finish_solve(&mut self, (block_number, mut decoder, result): SolveResult) {
+        self.in_flight -= 1;
+        let buffered = mem::take(&mut self.buffered_packets[block_number]);
+        let Ok(result) = result else {
+            self.states[block_number] = BlockState::Failed;
+            return;
+        };
+        if let Some(block) = result {
+            self.blocks[block_number] = Some(block);
+            self.states[block_number] = BlockState::Decoded;
+            self.completed_blocks.push(block_number as u8);
+            return;
+        }
+
+        // Decoding failed, so retry once more symbols are available
+        self.attempted_symbols[block_number] = decoder.received_symbols();
+        for packet in buffered {
+            let (payload_id, payload) = packet.split();
+            decoder.add_symbol(payload_id, payload);
+        }
+        self.states[block_number] = BlockState::Receiving(decoder);
+        self.mark_if_ready(block_number);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use rand::seq::SliceRandom;
+    use std::sync::{Mutex, mpsc};
+    use std::vec::Vec;
+
+    use crate::encoder::Encoder as EncoderNative;
+    use crate::{
+        DecodeError, EncodingPacket, ObjectTransmissionInformation, ParallelDecoder, PayloadId,
+    };
+
+    #[test]
+    fn random_erasure() {
+        let mut data: Vec<u8> = vec![0; 200_000];
+        for byte in data.iter_mut() {
+            *byte = rand::rng().random();
+        }
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 128, 12, 1, 8);
+        let encoder = EncoderNative::new(&data, config);
+        let mut packets = encoder.get_encoded_packets(15);
+        packets.shuffle(&mut rand::rng());
+        // Erase 10 packets at random
+        let length = packets.len();
+        packets.truncate(length - 10);
+
+        let mut decoder = ParallelDecoder::with_threads(config, 4);
+        let mut completed = vec![];
+        for packet in packets {
+            match decoder.add_new_packet(packet) {
+                Ok(()) | Err(DecodeError::AlreadyDecoded) => {}
+                Err(err) => panic!("{err}"),
+            }
+            completed.extend(decoder.poll_completed());
+        }
+        assert_eq!(decoder.join().unwrap().unwrap(), data);
+        assert!(decoder.is_complete());
+
+        completed.extend(decoder.poll_completed());
+        completed.sort();
+        assert_eq!(completed, (0..12).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn rejects_invalid_block() {
+        let config = ObjectTransmissionInformation::new(10_000, 100, 2, 1, 4);
+        let mut decoder = ParallelDecoder::new(config);
+        let packet = EncodingPacket::new(PayloadId::new(2, 0), vec![0; 100]);
+        assert_eq!(
+            decoder.add_new_packet(packet),
+            Err(DecodeError::InvalidSourceBlockNumber(2))
+        );
+        assert_eq!(decoder.join(), Ok(None));
+    }
+
+    #[test]
+    fn completion_callback() {
+        let data: Vec<u8> = (0..50_000).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 128, 4, 1, 8);
+        let encoder = EncoderNative::new(&data, config);
+
+        let mut decoder = ParallelDecoder::with_threads(config, 2);
+        let (sender, receiver) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        decoder.set_completion_callback(move |block| {
+            sender.lock().unwrap().send(block).unwrap();
+        });
+        for packet in encoder.get_encoded_packets(0) {
+            decoder.add_new_packet(packet).unwrap();
+        }
+        // Wait for every block without polling
+        let mut completed: Vec<u8> = (0..4).map(|_| receiver.recv().unwrap()).collect();
+        completed.sort();
+        assert_eq!(completed, [0, 1, 2, 3]);
+        assert_eq!(decoder.join().unwrap().unwrap(), data);
+    }
+
+    #[test]
+    fn solver_panic() {
+        let data: Vec<u8> = (0..10_000).map(|_| rand::rng().random()).collect();
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 100, 2, 1, 4);
+        let encoder = EncoderNative::new(&data, config);
+
+        let mut decoder = ParallelDecoder::with_threads(config, 2);
+        decoder.panic_in_solver = true;
+        let packets = encoder.get_encoded_packets(1);
+        let late_packet = packets[0].clone();
+        for packet in packets {
+            let _ = decoder.add_new_packet(packet);
+        }
+        assert_eq!(decoder.join(), Err(DecodeError::SolverFailed(0)));
+        assert_eq!(
+            decoder.add_new_packet(late_packet),
+            Err(DecodeError::SolverFailed(0))
+        );
+    }
+}
@@ -14,7 +14,7 @@ use crate::matrix::BinaryMatrix;
 use crate::octet::Octet;
 use crate::octet_matrix::DenseOctetMatrix;
 use crate::octets::BinaryOctetVec;
-use crate::operation_vector::SymbolOps;
+use crate::operation_vector::{SymbolOps, perform_ops};
 use crate::symbol_slab::SymbolSlab;
 use crate::systematic_constants::num_hdpc_symbols;
 use crate::systematic_constants::num_intermediate_symbols;
@@ -525,6 +525,25 @@ impl<T: BinaryMatrix> IntermediateSymbolDecoder<T> {
         matrix: T,
         symbols: SymbolSlab,
         num_source_symbols: u32,
+    ) -> IntermediateSymbolDecoder<T> {
+        IntermediateSymbolDecoder::new_binary(
+            matrix,
+            symbols,
+            num_source_symbols,
+            num_intermediate_symbols(num_source_symbols) as usize,
+            num_pi_symbols(num_source_symbols) as usize,
+        )
+    }
+
+    /// Creates a solver for a constraint matrix in GF(2) only, with `intermediate_symbols`
+    /// columns of which the last `inactive_symbols` are permanently inactivated.
+    /// Also used for the Raptor (RFC 5053) constraint matrix, which has no PI symbols.
+    pub fn new_binary(
+        matrix: T,
+        symbols: SymbolSlab,
+        num_source_symbols: u32,
+        intermediate_symbols: usize,
+        inactive_symbols: usize,
     ) -> IntermediateSymbolDecoder<T> {
         assert!(matrix.width() <= symbols.len());
         assert_eq!(matrix.height(), symbols.len());
@@ -537,12 +556,10 @@ impl<T: BinaryMatrix> IntermediateSymbolDecoder<T> {
             d.push(i);
         }
 
-        let intermediate_symbols = num_intermediate_symbols(num_source_symbols) as usize;
-        let pi_symbols = num_pi_symbols(num_source_symbols) as usize;
         #[cfg(debug_assertions)]
         let mut X = matrix.clone();
         #[cfg(debug_assertions)]
-        X.resize(X.height(), X.width() - pi_symbols);
+        X.resize(X.height(), X.width() - inactive_symbols);
 
         let mut A = matrix;
         A.enable_column_access_acceleration();
@@ -556,7 +573,7 @@ impl<T: BinaryMatrix> IntermediateSymbolDecoder<T> {
             c,
             d,
             i: 0,
-            u: pi_symbols,
+            u: inactive_symbols,
             L: intermediate_symbols,
             deferred_D_ops: Vec::with_capacity(70 * intermediate_symbols),
             num_source_symbols,
@@ -570,20 +587,8 @@ impl<T: BinaryMatrix> IntermediateSymbolDecoder<T> {
 
     #[inline(never)]
     fn apply_deferred_symbol_ops(&mut self) {
-        for op in self.deferred_D_ops.iter() {
-            match op {
-                SymbolOps::AddAssign { dest, src } => {
-                    self.D.add_assign(*dest, *src);
-                }
-                SymbolOps::MulAssign { dest, scalar } => {
-                    self.D.mulassign_scalar(*dest, scalar);
-                }
-                SymbolOps::FMA { dest, src, scalar } => {
-                    self.D.fma(*dest, *src, scalar);
-                }
-                SymbolOps::Reorder { order: _order } => {}
-            }
-        }
+        // The deferred ops never contain a Reorder, since it is only appended in execute()
+        perform_ops(&self.deferred_D_ops, &mut self.D);
     }
 
     // Returns true iff all elements in A between [start_row, end_row)
@@ -0,0 +1,474 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, RwLock};
+
+use crate::encoder::SourceBlockEncodingPlan;
+
+const DEFAULT_CAPACITY: usize = 64;
+
+/// Storage for the `SourceBlockEncodingPlan`s used by `SourceBlockEncoder::new`.
+///
+/// The built-in implementation is `LruEncodingPlanCache`. A custom implementation, for example one
+/// backed by shared memory so that plans are shared across processes, can be installed with
+/// `set_encoding_plan_cache`.
+pub trait EncodingPlanCache: Send + Sync {
+    /// Returns the cached plan for `symbol_count` source symbols, if there is one.
+    fn get(&self, symbol_count: u16) -> Option<Arc<SourceBlockEncodingPlan>>;
+
+    /// Stores a newly generated or loaded plan. The cache may discard it.
+    fn insert(&self, plan: Arc<SourceBlockEncodingPlan>);
+}
+
+/// Counters describing the activity of an `LruEncodingPlanCache`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EncodingPlanCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    /// Number of plans currently cached.
+    pub entries: usize,
+    /// Approximate memory used by the cached plans.
+    pub bytes: usize,
+}
+
+struct CacheEntry {
+    plan: Arc<SourceBlockEncodingPlan>,
+    bytes: usize,
+    last_used: u64,
+    pinned: bool,
+}
+
+#[derive(Default)]
+struct LruState {
+    entries: HashMap<u16, CacheEntry>,
+    capacity: usize,
+    byte_budget: Option<usize>,
+    // Incremented on every access, to order entries by recency
+    clock: u64,
+    stats: EncodingPlanCacheStats,
+}
+
+impl LruState {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn over_limit(&self) -> bool {
+        let over_budget = self
+            .byte_budget
+            .is_some_and(|budget| self.stats.bytes > budget);
+        self.entries.len() > self.capacity || over_budget
+    }
+
+    // Evicts least recently used, unpinned, entries until the cache is within its limits
+    fn evict(&mut self) {
+        while self.over_limit() {
+            let victim = self
+                .entries
+                .iter()
+                .filter(|(_, entry)| !entry.pinned)
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(symbol_count, _)| *symbol_count);
+            let Some(victim) = victim else {
+                // Everything left is pinned
+                return;
+            };
+            let entry = self.entries.remove(&victim).unwrap();
+            self.stats.bytes -= entry.bytes;
+            self.stats.evictions += 1;
+        }
+    }
+
+    fn insert(&mut self, plan: Arc<SourceBlockEncodingPlan>, pinned: bool) {
+        let bytes = plan.size_in_bytes();
+        let last_used = self.tick();
+        let symbol_count = plan.source_symbol_count();
+        let entry = CacheEntry {
+            plan,
+            bytes,
+            last_used,
+            pinned,
+        };
+        if let Some(previous) = self.entries.insert(symbol_count, entry) {
+            self.stats.bytes -= previous.bytes;
+            if previous.pinned {
+                self.entries.get_mut(&symbol_count).unwrap().pinned = true;
+            }
+        }
+        self.stats.bytes += bytes;
+        self.evict();
+    }
+}
+
+/// Thread-safe plan cache with least recently used eviction.
+///
+/// The cache is bounded by a maximum number of plans, and optionally by the approximate number of
+/// bytes used by the plans. Pinned plans are never evicted, and do count towards the limits.
+pub struct LruEncodingPlanCache {
+    state: Mutex<LruState>,
+}
+
+impl LruEncodingPlanCache {
+    /// Creates a cache holding at most `capacity` plans. A capacity of zero disables caching of
+    /// plans which are not pinned.
+    pub fn new(capacity: usize) -> LruEncodingPlanCache {
+        LruEncodingPlanCache {
+            state: Mutex::new(LruState {
+                capacity,
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LruState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.lock().capacity
+    }
+
+    /// Sets the maximum number of cached plans, evicting plans if necessary.
+    pub fn set_capacity(&self, capacity: usize) {
+        let mut state = self.lock();
+        state.capacity = capacity;
+        state.evict();
+    }
+
+    pub fn byte_budget(&self) -> Option<usize> {
+        self.lock().byte_budget
+    }
+
+    /// Limits the approximate memory used by cached plans, evicting plans if necessary. `None`
+    /// removes the limit.
+    pub fn set_byte_budget(&self, byte_budget: Option<usize>) {
+        let mut state = self.lock();
+        state.byte_budget = byte_budget;
+        state.evict();
+    }
+
+    /// Removes every plan, including pinned ones. Statistics are not reset.
+    pub fn clear(&self) {
+        let mut state = self.lock();
+        state.entries.clear();
+        state.stats.bytes = 0;
+    }
+
+    /// Ensures the plan for `symbol_count` source symbols is cached, generating it if needed, and
+    /// protects it from eviction.
+    pub fn pin(&self, symbol_count: u16) {
+        {
+            let mut state = self.lock();
+            if let Some(entry) = state.entries.get_mut(&symbol_count) {
+                entry.pinned = true;
+                return;
+            }
+        }
+        let plan = Arc::new(SourceBlockEncodingPlan::generate(symbol_count));
+        self.lock().insert(plan, true);
+    }
+
+    /// Allows the plan for `symbol_count` source symbols to be evicted again.
+    pub fn unpin(&self, symbol_count: u16) {
+        let mut state = self.lock();
+        if let Some(entry) = state.entries.get_mut(&symbol_count) {
+            entry.pinned = false;
+        }
+        state.evict();
+    }
+
+    /// Returns true if a plan for `symbol_count` source symbols is cached. Does not count as a
+    /// use of the plan.
+    pub fn contains(&self, symbol_count: u16) -> bool {
+        self.lock().entries.contains_key(&symbol_count)
+    }
+
+    pub fn stats(&self) -> EncodingPlanCacheStats {
+        let state = self.lock();
+        EncodingPlanCacheStats {
+            entries: state.entries.len(),
+            ..state.stats
+        }
+    }
+
+    /// Resets the hit, miss, and eviction counters.
+    pub fn reset_stats(&self) {
+        let mut state = self.lock();
+        state.stats = EncodingPlanCacheStats {
+            bytes: state.stats.bytes,
+            ..Default::default()
+        };
+    }
+}
+
+impl Default for LruEncodingPlanCache {
+    fn default() -> LruEncodingPlanCache {
+        LruEncodingPlanCache::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl EncodingPlanCache for LruEncodingPlanCache {
+    fn get(&self, symbol_count: u16) -> Option<Arc<SourceBlockEncodingPlan>> {
+        let mut state = self.lock();
+        let now = state.tick();
+        match state.entries.get_mut(&symbol_count) {
+            Some(entry) => {
+                entry.last_used = now;
+                let plan = Arc::clone(&entry.plan);
+                state.stats.hits += 1;
+                Some(plan)
+            }
+            None => {
+                state.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn insert(&self, plan: Arc<SourceBlockEncodingPlan>) {
+        self.lock().insert(plan, false);
+    }
+}
+
+/// Returns the built-in global plan cache, which holds 64 plans by default.
+pub fn encoding_plan_cache() -> &'static LruEncodingPlanCache {
+    static CACHE: OnceLock<LruEncodingPlanCache> = OnceLock::new();
+    CACHE.get_or_init(LruEncodingPlanCache::default)
+}
+
+fn custom_cache() -> &'static RwLock<Option<Arc<dyn EncodingPlanCache>>> {
+    static CUSTOM: OnceLock<RwLock<Option<Arc<dyn EncodingPlanCache>>>> = OnceLock::new();
+    CUSTOM.get_or_init(|| RwLock::new(None))
+}
+
+/// Replaces the global plan cache with `cache`, or restores the built-in cache if `None`.
+pub fn set_encoding_plan_cache(cache: Option<Arc<dyn EncodingPlanCache>>) {
+    *custom_cache()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = cache;
+}
+
+// Calls f with the cache which is currently in use
+fn with_active_cache<T>(f: impl FnOnce(&dyn EncodingPlanCache) -> T) -> T {
+    let custom = custom_cache()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+    match custom {
+        Some(cache) => f(cache.as_ref()),
+        None => f(encoding_plan_cache()),
+    }
+}
+
+type PendingPlan = Arc<OnceLock<Arc<SourceBlockEncodingPlan>>>;
+
+// Plans which are being generated, so that threads which miss the cache at the same time wait for
+// a single generation of each plan
+fn pending_plans() -> MutexGuard<'static, HashMap<u16, PendingPlan>> {
+    static PENDING: OnceLock<Mutex<HashMap<u16, PendingPlan>>> = OnceLock::new();
+    PENDING
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub(crate) fn get_or_generate_plan(symbol_count: u16) -> Arc<SourceBlockEncodingPlan> {
+    if let Some(plan) = with_active_cache(|cache| cache.get(symbol_count)) {
+        return plan;
+    }
+
+    let pending = Arc::clone(pending_plans().entry(symbol_count).or_default());
+    let plan = Arc::clone(pending.get_or_init(|| {
+        // The plan may have been cached between the lookup above and the registration of the
+        // pending generation
+        with_active_cache(|cache| cache.get(symbol_count)).unwrap_or_else(|| {
+            let generated = Arc::new(SourceBlockEncodingPlan::generate(symbol_count));
+            with_active_cache(|cache| cache.insert(Arc::clone(&generated)));
+            generated
+        })
+    }));
+
+    let mut pending_plans = pending_plans();
+    if pending_plans
+        .get(&symbol_count)
+        .is_some_and(|entry| Arc::ptr_eq(entry, &pending))
+    {
+        pending_plans.remove(&symbol_count);
+    }
+    plan
+}
+
+pub(crate) fn insert_plan(plan: SourceBlockEncodingPlan) {
+    with_active_cache(|cache| cache.insert(Arc::new(plan)));
+}
+
+// Held by tests which replace, or depend on the contents of, the global cache, since tests run
+// concurrently
+#[cfg(test)]
+pub(crate) fn lock_global_cache_for_test() -> MutexGuard<'static, ()> {
+    static LOCK: Mutex<()> = Mutex::new(());
+    LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    use crate::plan_cache::{
+        EncodingPlanCache, LruEncodingPlanCache, get_or_generate_plan, lock_global_cache_for_test,
+    };
+    use crate::{
+        ObjectTransmissionInformation, SourceBlockEncoder, SourceBlockEncodingPlan,
+        set_encoding_plan_cache,
+    };
+
+    fn plan(symbol_count: u16) -> Arc<SourceBlockEncodingPlan> {
+        Arc::new(SourceBlockEncodingPlan::generate(symbol_count))
+    }
+
+    #[test]
+    fn least_recently_used_eviction() {
+        let cache = LruEncodingPlanCache::new(2);
+        cache.insert(plan(10));
+        cache.insert(plan(11));
+        assert!(cache.get(10).is_some());
+        cache.insert(plan(12));
+        assert!(cache.contains(10));
+        assert!(!cache.contains(11));
+        assert!(cache.contains(12));
+
+        assert!(cache.get(11).is_none());
+        let stats = cache.stats();
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.evictions, 1);
+        assert_eq!(stats.entries, 2);
+        assert_eq!(
+            stats.bytes,
+            plan(10).size_in_bytes() + plan(12).size_in_bytes()
+        );
+
+        cache.set_capacity(1);
+        assert_eq!(cache.stats().entries, 1);
+        cache.clear();
+        assert_eq!(cache.stats().entries, 0);
+        assert_eq!(cache.stats().bytes, 0);
+    }
+
+    #[test]
+    fn byte_budget() {
+        let cache = LruEncodingPlanCache::new(10);
+        cache.set_byte_budget(Some(plan(10).size_in_bytes() + plan(11).size_in_bytes()));
+        cache.insert(plan(10));
+        cache.insert(plan(11));
+        assert_eq!(cache.stats().entries, 2);
+        cache.insert(plan(12));
+        assert!(!cache.contains(10));
+        assert!(cache.stats().bytes <= cache.byte_budget().unwrap());
+    }
+
+    #[test]
+    fn pinned_plans_are_not_evicted() {
+        let cache = LruEncodingPlanCache::new(1);
+        cache.pin(10);
+        cache.insert(plan(11));
+        cache.insert(plan(12));
+        assert!(cache.contains(10));
+        assert_eq!(cache.get(10).unwrap(), plan(10));
+
+        assert!(!cache.contains(11));
+        assert!(!cache.contains(12));
+
+        cache.unpin(10);
+        cache.insert(plan(11));
+        assert!(!cache.contains(10));
+        assert!(cache.contains(11));
+
+        let disabled = LruEncodingPlanCache::new(0);
+        disabled.insert(plan(10));
+        assert!(!disabled.contains(10));
+    }
+
+    #[derive(Default)]
+    struct CountingCache {
+        inner: LruEncodingPlanCache,
+        lookups: AtomicUsize,
+    }
+
+    impl EncodingPlanCache for CountingCache {
+        fn get(&self, symbol_count: u16) -> Option<Arc<SourceBlockEncodingPlan>> {
+            self.lookups.fetch_add(1, Ordering::SeqCst);
+            self.inner.get(symbol_count)
+        }
+
+        fn insert(&self, plan: Arc<SourceBlockEncodingPlan>) {
+            self.inner.insert(plan);
+        }
+    }
+
+    #[derive(Default)]
+    struct InsertCountingCache {
+        inner: LruEncodingPlanCache,
+        inserts: AtomicUsize,
+    }
+
+    impl EncodingPlanCache for InsertCountingCache {
+        fn get(&self, symbol_count: u16) -> Option<Arc<SourceBlockEncodingPlan>> {
+            self.inner.get(symbol_count)
+        }
+
+        fn insert(&self, plan: Arc<SourceBlockEncodingPlan>) {
+            self.inserts.fetch_add(1, Ordering::SeqCst);
+            self.inner.insert(plan);
+        }
+    }
+
+    #[test]
+    fn concurrent_misses_generate_once() {
+        let _lock = lock_global_cache_for_test();
+        let cache = Arc::new(InsertCountingCache::default());
+        set_encoding_plan_cache(Some(cache.clone()));
+        let barrier = Barrier::new(8);
+        let plans: Vec<_> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| {
+                    scope.spawn(|| {
+                        barrier.wait();
+                        get_or_generate_plan(300)
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+        set_encoding_plan_cache(None);
+
+        assert_eq!(cache.inserts.load(Ordering::SeqCst), 1);
+        for plan in plans.iter() {
+            assert_eq!(**plan, *plans[0]);
+        }
+    }
+
+    #[test]
+    fn custom_cache() {
+        let _lock = lock_global_cache_for_test();
+        let cache = Arc::new(CountingCache::default());
+        set_encoding_plan_cache(Some(cache.clone()));
+        let config = ObjectTransmissionInformation::new(40, 4, 1, 1, 4);
+        let encoder = SourceBlockEncoder::new(0, &config, &[1; 40]);
+        set_encoding_plan_cache(None);
+
+        assert!(cache.lookups.load(Ordering::SeqCst) >= 1);
+        assert!(cache.inner.contains(10));
+        assert_eq!(
+            encoder,
+            SourceBlockEncoder::with_encoding_plan(0, &config, &[1; 40], &plan(10))
+        );
+    }
+}
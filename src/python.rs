@@ -1,25 +1,26 @@
 use std::vec::Vec;
 
-use crate::base::{EncodingPacket, ObjectTransmissionInformation};
-use crate::decoder::Decoder as DecoderNative;
+use crate::base::{EncodingPacketRef, ObjectTransmissionInformation};
+use crate::decoder::{DecodeError, Decoder as DecoderNative};
 use crate::encoder::Encoder as EncoderNative;
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use pyo3::types::*;
 
-#[pyclass]
-pub struct Encoder {
+#[pyclass(name = "Encoder")]
+pub struct PyEncoder {
     encoder: EncoderNative,
 }
 
 #[pymethods]
-impl Encoder {
+impl PyEncoder {
     #[staticmethod]
     pub fn with_defaults(
         data: Bound<'_, PyBytes>,
         maximum_transmission_unit: u16,
-    ) -> PyResult<Encoder> {
+    ) -> PyResult<PyEncoder> {
         let encoder = EncoderNative::with_defaults(data.as_bytes(), maximum_transmission_unit);
-        Ok(Encoder { encoder })
+        Ok(PyEncoder { encoder })
     }
 
     pub fn get_encoded_packets(
@@ -38,24 +39,24 @@ impl Encoder {
     }
 }
 
-#[pyclass]
-pub struct Decoder {
+#[pyclass(name = "Decoder")]
+pub struct PyDecoder {
     decoder: DecoderNative,
 }
 
 #[pymethods]
-impl Decoder {
+impl PyDecoder {
     #[staticmethod]
     pub fn with_defaults(
         transfer_length: u64,
         maximum_transmission_unit: u16,
-    ) -> PyResult<Decoder> {
+    ) -> PyResult<PyDecoder> {
         let config = ObjectTransmissionInformation::with_defaults(
             transfer_length,
             maximum_transmission_unit,
         );
         let decoder = DecoderNative::new(config);
-        Ok(Decoder { decoder })
+        Ok(PyDecoder { decoder })
     }
 
     pub fn decode(
@@ -63,16 +64,21 @@ impl Decoder {
         py: Python<'_>,
         packet: Bound<'_, PyBytes>,
     ) -> PyResult<Option<Py<PyBytes>>> {
-        let result = self
-            .decoder
-            .decode(EncodingPacket::deserialize(packet.as_bytes()));
-        Ok(result.map(|data| PyBytes::new(py, &data).into()))
+        let packet = EncodingPacketRef::try_deserialize(packet.as_bytes())
+            .map_err(|err| PyValueError::new_err(err.to_string()))?;
+        // Extra packets for blocks which have already been decoded are harmless, so only
+        // malformed packets raise
+        match self.decoder.try_decode_ref(packet) {
+            Ok(Some(data)) => Ok(Some(PyBytes::new(py, &data).into())),
+            Ok(None) | Err(DecodeError::AlreadyDecoded) => Ok(None),
+            Err(err) => Err(PyValueError::new_err(err.to_string())),
+        }
     }
 }
 
 #[pymodule]
 pub fn raptorq(_py: Python<'_>, m: Bound<'_, PyModule>) -> PyResult<()> {
-    m.add_class::<Encoder>()?;
-    m.add_class::<Decoder>()?;
+    m.add_class::<PyEncoder>()?;
+    m.add_class::<PyDecoder>()?;
     Ok(())
 }
@@ -0,0 +1,1098 @@
+#[cfg(feature = "std")]
+use std::{collections::HashSet as Set, vec::Vec};
+
+#[cfg(not(feature = "std"))]
+use alloc::{collections::BTreeSet as Set, vec::Vec};
+
+use core::fmt;
+
+use crate::base::partition;
+use crate::matrix::{BinaryMatrix, DenseBinaryMatrix};
+use crate::octet::Octet;
+use crate::octets::add_assign;
+use crate::pi_solver::IntermediateSymbolDecoder;
+use crate::rng::r10_rand;
+use crate::symbol_slab::SymbolSlab;
+#[cfg(feature = "serde_support")]
+use serde::{Deserialize, Serialize};
+
+/// FEC Encoding ID of Raptor, as registered in RFC 5053.
+pub const RAPTOR_FEC_ENCODING_ID: u8 = 1;
+/// Smallest number of source symbols in a Raptor source block, K_min of RFC 5053.
+pub const R10_MIN_SOURCE_SYMBOLS_PER_BLOCK: u32 = 4;
+/// Largest number of source symbols in a source block supported by `R10Encoder` and
+/// `R10Decoder`. RFC 5053 allows up to 8192, but only the systematic indices of smaller blocks
+/// are included so far, so objects from senders which use larger blocks are rejected with
+/// `R10Error::UnsupportedSourceBlockLength` rather than decoded incorrectly.
+pub const R10_MAX_SOURCE_SYMBOLS_PER_BLOCK: u32 = 213;
+
+// The Transfer Length field is 48 bits
+const MAX_TRANSFER_LENGTH: u64 = (1 << 48) - 1;
+
+// Systematic indices J(K) of RFC 5053 section 5.7, for K = 4, 5, ..., 213. The remaining
+// entries, up to K = 8192, must be copied from the RFC: J(K) is not the smallest index giving an
+// invertible matrix, so it cannot be computed
+#[rustfmt::skip]
+const SYSTEMATIC_INDICES: [u32; 210] = [
+    18, 14, 61, 46, 14, 22, 20, 40, 48, 1, 29, 40, 43, 46, 18,
+    8, 20, 2, 61, 26, 13, 29, 36, 19, 58, 5, 58, 0, 54, 56,
+    24, 14, 5, 67, 39, 31, 25, 29, 24, 19, 14, 56, 49, 49, 63,
+    30, 4, 39, 2, 1, 20, 19, 61, 4, 54, 70, 25, 52, 9, 26,
+    55, 69, 27, 68, 75, 19, 64, 57, 45, 3, 37, 31, 100, 41, 25,
+    41, 53, 23, 9, 31, 26, 30, 30, 46, 90, 50, 13, 90, 77, 61,
+    31, 54, 54, 3, 21, 66, 21, 11, 23, 11, 29, 21, 7, 1, 27,
+    4, 34, 17, 85, 69, 17, 75, 93, 57, 0, 53, 71, 88, 119, 88,
+    90, 22, 0, 58, 41, 22, 96, 26, 79, 118, 19, 3, 81, 72, 50,
+    0, 32, 79, 28, 25, 12, 25, 29, 3, 37, 30, 30, 41, 84, 32,
+    31, 61, 32, 61, 7, 56, 54, 39, 33, 66, 29, 3, 14, 75, 75,
+    78, 84, 75, 84, 25, 54, 25, 25, 107, 78, 27, 73, 0, 49, 96,
+    53, 50, 21, 10, 73, 58, 65, 27, 3, 27, 18, 54, 45, 69, 29,
+    3, 65, 31, 71, 76, 56, 54, 76, 54, 13, 5, 18, 142, 17, 3,
+];
+
+// Deg[v] of RFC 5053, as the exclusive upper bound of v for each degree
+const DEGREE_DISTRIBUTION: [(u32, u32); 7] = [
+    (10241, 1),
+    (491582, 2),
+    (712794, 3),
+    (831695, 4),
+    (948446, 10),
+    (1032189, 11),
+    (1048576, 40),
+];
+
+/// Reason Raptor parameters or packets were rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum R10Error {
+    /// The object transmission information is inconsistent, for example a zero symbol size or
+    /// more source blocks than source symbols.
+    InvalidParameters,
+    /// A source block would have a number of source symbols outside the supported range, see
+    /// `R10_MAX_SOURCE_SYMBOLS_PER_BLOCK`.
+    UnsupportedSourceBlockLength(u64),
+    /// The source block number does not refer to a block of the object.
+    InvalidSourceBlockNumber(u16),
+    /// The payload length differs from the symbol size.
+    InvalidSymbolLength { expected: usize, actual: usize },
+    /// The serialized packet, of this many bytes, is shorter than its 4 byte payload ID.
+    TruncatedPacket(usize),
+}
+
+impl fmt::Display for R10Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            R10Error::InvalidParameters => write!(f, "invalid object transmission information"),
+            R10Error::UnsupportedSourceBlockLength(symbols) => write!(
+                f,
+                "{symbols} source symbols per block is outside the supported range of \
+                 {R10_MIN_SOURCE_SYMBOLS_PER_BLOCK} to {R10_MAX_SOURCE_SYMBOLS_PER_BLOCK}"
+            ),
+            R10Error::InvalidSourceBlockNumber(sbn) => {
+                write!(f, "invalid source block number {sbn}")
+            }
+            R10Error::InvalidSymbolLength { expected, actual } => {
+                write!(f, "expected symbol of {expected} bytes, got {actual} bytes")
+            }
+            R10Error::TruncatedPacket(length) => {
+                write!(f, "packet of {length} bytes is shorter than its payload id")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for R10Error {}
+
+/// FEC Object Transmission Information of Raptor, as defined in RFC 5053 section 3.2.
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct R10ObjectTransmissionInformation {
+    transfer_length: u64, // Limited to u48
+    symbol_size: u16,
+    num_source_blocks: u16,
+    num_sub_blocks: u8,
+    symbol_alignment: u8,
+}
+
+impl R10ObjectTransmissionInformation {
+    /// Panics if the parameters are rejected by `validate`.
+    pub fn new(
+        transfer_length: u64,
+        symbol_size: u16,
+        source_blocks: u16,
+        sub_blocks: u8,
+        alignment: u8,
+    ) -> R10ObjectTransmissionInformation {
+        match R10ObjectTransmissionInformation::try_new(
+            transfer_length,
+            symbol_size,
+            source_blocks,
+            sub_blocks,
+            alignment,
+        ) {
+            Ok(config) => config,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Like `new`, but returns an error instead of panicking.
+    pub fn try_new(
+        transfer_length: u64,
+        symbol_size: u16,
+        source_blocks: u16,
+        sub_blocks: u8,
+        alignment: u8,
+    ) -> Result<R10ObjectTransmissionInformation, R10Error> {
+        let config = R10ObjectTransmissionInformation {
+            transfer_length,
+            symbol_size,
+            num_source_blocks: source_blocks,
+            num_sub_blocks: sub_blocks,
+            symbol_alignment: alignment,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks that the parameters are consistent, and that every source block has a supported
+    /// number of source symbols.
+    pub fn validate(&self) -> Result<(), R10Error> {
+        if self.transfer_length > MAX_TRANSFER_LENGTH
+            || self.symbol_size == 0
+            || self.symbol_alignment == 0
+            || !self
+                .symbol_size
+                .is_multiple_of(self.symbol_alignment as u16)
+            || self.num_source_blocks == 0
+            || self.num_sub_blocks == 0
+            || self.num_sub_blocks as u16 > self.symbol_size / self.symbol_alignment as u16
+        {
+            return Err(R10Error::InvalidParameters);
+        }
+        let source_symbols = self.transfer_length.div_ceil(self.symbol_size as u64);
+        if self.num_source_blocks as u64 > source_symbols {
+            return Err(R10Error::InvalidParameters);
+        }
+        let largest_block = source_symbols.div_ceil(self.num_source_blocks as u64);
+        if largest_block > R10_MAX_SOURCE_SYMBOLS_PER_BLOCK as u64 {
+            return Err(R10Error::UnsupportedSourceBlockLength(largest_block));
+        }
+        let smallest_block = source_symbols / self.num_source_blocks as u64;
+        if smallest_block < R10_MIN_SOURCE_SYMBOLS_PER_BLOCK as u64 {
+            return Err(R10Error::UnsupportedSourceBlockLength(smallest_block));
+        }
+        Ok(())
+    }
+
+    /// Parses the Common FEC OTI, a 48 bit transfer length, 16 reserved bits and the 16 bit
+    /// symbol size, followed by the Scheme-Specific FEC OTI: the 16 bit number of source blocks,
+    /// and the 8 bit number of sub-blocks and symbol alignment.
+    pub fn deserialize(data: &[u8; 14]) -> R10ObjectTransmissionInformation {
+        let mut transfer_length = [0; 8];
+        transfer_length[2..].copy_from_slice(&data[..6]);
+        R10ObjectTransmissionInformation {
+            transfer_length: u64::from_be_bytes(transfer_length),
+            symbol_size: u16::from_be_bytes([data[8], data[9]]),
+            num_source_blocks: u16::from_be_bytes([data[10], data[11]]),
+            num_sub_blocks: data[12],
+            symbol_alignment: data[13],
+        }
+    }
+
+    /// Like `deserialize`, but validates the decoded parameters.
+    pub fn try_deserialize(data: &[u8; 14]) -> Result<R10ObjectTransmissionInformation, R10Error> {
+        let config = R10ObjectTransmissionInformation::deserialize(data);
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn serialize(&self) -> [u8; 14] {
+        let mut serialized = [0; 14];
+        serialized[..6].copy_from_slice(&self.transfer_length.to_be_bytes()[2..]);
+        // Bytes 6 and 7 are reserved
+        serialized[8..10].copy_from_slice(&self.symbol_size.to_be_bytes());
+        serialized[10..12].copy_from_slice(&self.num_source_blocks.to_be_bytes());
+        serialized[12] = self.num_sub_blocks;
+        serialized[13] = self.symbol_alignment;
+        serialized
+    }
+
+    pub fn transfer_length(&self) -> u64 {
+        self.transfer_length
+    }
+
+    pub fn symbol_size(&self) -> u16 {
+        self.symbol_size
+    }
+
+    pub fn source_blocks(&self) -> u16 {
+        self.num_source_blocks
+    }
+
+    pub fn sub_blocks(&self) -> u8 {
+        self.num_sub_blocks
+    }
+
+    pub fn symbol_alignment(&self) -> u8 {
+        self.symbol_alignment
+    }
+
+    // Number of source symbols in each source block, as in section 4.4.1.2 of RFC 6330, which
+    // RFC 5053 shares
+    fn source_block_lengths(&self) -> Vec<u32> {
+        let source_symbols = self.transfer_length.div_ceil(self.symbol_size as u64) as u32;
+        let (kl, ks, zl, zs) = partition(source_symbols, self.num_source_blocks);
+        let mut lengths = vec![kl; zl as usize];
+        lengths.extend(vec![ks; zs as usize]);
+        lengths
+    }
+
+    // Number of bytes each sub-block contributes to a symbol
+    fn sub_symbol_sizes(&self) -> Vec<usize> {
+        let (tl, ts, nl, ns) = partition(
+            (self.symbol_size / self.symbol_alignment as u16) as u32,
+            self.num_sub_blocks,
+        );
+        (0..(nl + ns))
+            .map(|i| if i < nl { tl } else { ts } as usize * self.symbol_alignment as usize)
+            .collect()
+    }
+}
+
+/// FEC Payload ID of Raptor, as defined in RFC 5053 section 3.1.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct R10PayloadId {
+    source_block_number: u16,
+    encoding_symbol_id: u16,
+}
+
+impl R10PayloadId {
+    pub fn new(source_block_number: u16, encoding_symbol_id: u16) -> R10PayloadId {
+        R10PayloadId {
+            source_block_number,
+            encoding_symbol_id,
+        }
+    }
+
+    pub fn deserialize(data: &[u8; 4]) -> R10PayloadId {
+        R10PayloadId {
+            source_block_number: u16::from_be_bytes([data[0], data[1]]),
+            encoding_symbol_id: u16::from_be_bytes([data[2], data[3]]),
+        }
+    }
+
+    pub fn serialize(&self) -> [u8; 4] {
+        let mut serialized = [0; 4];
+        serialized[..2].copy_from_slice(&self.source_block_number.to_be_bytes());
+        serialized[2..].copy_from_slice(&self.encoding_symbol_id.to_be_bytes());
+        serialized
+    }
+
+    pub fn source_block_number(&self) -> u16 {
+        self.source_block_number
+    }
+
+    pub fn encoding_symbol_id(&self) -> u16 {
+        self.encoding_symbol_id
+    }
+}
+
+/// Encoding symbol of Raptor, along with its FEC Payload ID.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct R10EncodingPacket {
+    payload_id: R10PayloadId,
+    data: Vec<u8>,
+}
+
+impl R10EncodingPacket {
+    pub fn new(payload_id: R10PayloadId, data: Vec<u8>) -> R10EncodingPacket {
+        R10EncodingPacket { payload_id, data }
+    }
+
+    /// Panics if `data` is shorter than the payload ID, see `try_deserialize`.
+    pub fn deserialize(data: &[u8]) -> R10EncodingPacket {
+        match R10EncodingPacket::try_deserialize(data) {
+            Ok(packet) => packet,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Like `deserialize`, but returns `R10Error::TruncatedPacket` instead of panicking if `data`
+    /// is shorter than the payload ID.
+    pub fn try_deserialize(data: &[u8]) -> Result<R10EncodingPacket, R10Error> {
+        let (payload_id, symbol) = data
+            .split_first_chunk::<4>()
+            .ok_or(R10Error::TruncatedPacket(data.len()))?;
+        Ok(R10EncodingPacket {
+            payload_id: R10PayloadId::deserialize(payload_id),
+            data: Vec::from(symbol),
+        })
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut serialized = Vec::with_capacity(4 + self.data.len());
+        serialized.extend_from_slice(&self.payload_id.serialize());
+        serialized.extend_from_slice(&self.data);
+        serialized
+    }
+
+    pub fn payload_id(&self) -> &R10PayloadId {
+        &self.payload_id
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn split(self) -> (R10PayloadId, Vec<u8>) {
+        (self.payload_id, self.data)
+    }
+}
+
+fn is_prime(n: u32) -> bool {
+    n >= 2
+        && (2..)
+            .take_while(|d| d * d <= n)
+            .all(|d| !n.is_multiple_of(d))
+}
+
+fn next_prime(n: u32) -> u32 {
+    (n..).find(|&n| is_prime(n)).unwrap()
+}
+
+fn binomial(n: u32, k: u32) -> u64 {
+    (0..k as u64).fold(1, |result, i| result * (n as u64 - i) / (i + 1))
+}
+
+// Code parameters of a source block, as derived in RFC 5053 section 5.4.2.3
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct BlockParameters {
+    source_symbols: u32,
+    ldpc_symbols: u32,
+    half_symbols: u32,
+    intermediate_symbols: u32,
+    intermediate_symbols_prime: u32,
+    systematic_index: u32,
+}
+
+impl BlockParameters {
+    fn new(source_symbols: u32) -> BlockParameters {
+        assert!(
+            (R10_MIN_SOURCE_SYMBOLS_PER_BLOCK..=R10_MAX_SOURCE_SYMBOLS_PER_BLOCK)
+                .contains(&source_symbols)
+        );
+        let k = source_symbols;
+        let x = (1..).find(|x| x * (x - 1) >= 2 * k).unwrap();
+        let s = next_prime(k.div_ceil(100) + x);
+        let h = (1..)
+            .find(|&h| binomial(h, h.div_ceil(2)) >= (k + s) as u64)
+            .unwrap();
+        let l = k + s + h;
+        BlockParameters {
+            source_symbols: k,
+            ldpc_symbols: s,
+            half_symbols: h,
+            intermediate_symbols: l,
+            intermediate_symbols_prime: next_prime(l),
+            systematic_index: SYSTEMATIC_INDICES[(k - R10_MIN_SOURCE_SYMBOLS_PER_BLOCK) as usize],
+        }
+    }
+
+    // Trip[K, X] of RFC 5053 section 5.4.4.4
+    fn triple(&self, encoding_symbol_id: u32) -> (u32, u32, u32) {
+        const Q: u64 = 65521;
+        let j = self.systematic_index as u64;
+        let a = (53591 + j * 997) % Q;
+        let b = 10267 * (j + 1) % Q;
+        let y = ((b + encoding_symbol_id as u64 * a) % Q) as u32;
+        let v = r10_rand(y, 0, 1 << 20);
+        let d = DEGREE_DISTRIBUTION
+            .iter()
+            .find(|(bound, _)| v < *bound)
+            .unwrap()
+            .1;
+        let a = 1 + r10_rand(y, 1, self.intermediate_symbols_prime - 1);
+        let b = r10_rand(y, 2, self.intermediate_symbols_prime);
+        (d, a, b)
+    }
+
+    // Calls f with the index of each intermediate symbol combined by LTEnc[K, C, Trip[K, X]],
+    // RFC 5053 section 5.4.4.3
+    fn lt_indices<F: FnMut(usize)>(&self, encoding_symbol_id: u32, mut f: F) {
+        let l = self.intermediate_symbols;
+        let lp = self.intermediate_symbols_prime;
+        let (d, a, mut b) = self.triple(encoding_symbol_id);
+        while b >= l {
+            b = (b + a) % lp;
+        }
+        f(b as usize);
+        for _ in 1..d.min(l) {
+            b = (b + a) % lp;
+            while b >= l {
+                b = (b + a) % lp;
+            }
+            f(b as usize);
+        }
+    }
+
+    // Constraint matrix of the LDPC and Half symbols, followed by the LT rows of the given
+    // encoding symbols, RFC 5053 section 5.4.2.4.2
+    fn constraint_matrix(&self, encoding_symbol_ids: &[u32]) -> DenseBinaryMatrix {
+        let k = self.source_symbols as usize;
+        let s = self.ldpc_symbols as usize;
+        let h = self.half_symbols as usize;
+        let mut matrix = DenseBinaryMatrix::new(
+            s + h + encoding_symbol_ids.len(),
+            self.intermediate_symbols as usize,
+            0,
+        );
+
+        // G_LDPC and I_S
+        for i in 0..k {
+            let a = 1 + (i / s) % (s - 1);
+            let mut b = i % s;
+            for _ in 0..3 {
+                matrix.set(b, i, Octet::one());
+                b = (b + a) % s;
+            }
+        }
+        for i in 0..s {
+            matrix.set(i, k + i, Octet::one());
+        }
+
+        // G_Half and I_H. Each column of G_Half is the next Gray code with ceil(H/2) bits set.
+        let half_weight = h.div_ceil(2) as u32;
+        let gray_codes = (0u32..)
+            .map(|i| i ^ (i >> 1))
+            .filter(|code| code.count_ones() == half_weight);
+        for (column, code) in gray_codes.take(k + s).enumerate() {
+            for row in 0..h {
+                if (code >> row) & 1 == 1 {
+                    matrix.set(s + row, column, Octet::one());
+                }
+            }
+        }
+        for i in 0..h {
+            matrix.set(s + i, k + s + i, Octet::one());
+        }
+
+        // G_LT
+        for (row, &encoding_symbol_id) in encoding_symbol_ids.iter().enumerate() {
+            self.lt_indices(encoding_symbol_id, |column| {
+                matrix.set(s + h + row, column, Octet::one())
+            });
+        }
+        matrix
+    }
+
+    // Solves for the intermediate symbols from encoding symbols with the given ESIs, returning
+    // None if they do not determine the intermediate symbols
+    fn intermediate_symbols(
+        &self,
+        encoding_symbol_ids: &[u32],
+        symbols: &[&[u8]],
+        symbol_size: usize,
+    ) -> Option<SymbolSlab> {
+        let constraint_rows = (self.ldpc_symbols + self.half_symbols) as usize;
+        let mut d = SymbolSlab::with_zeros(constraint_rows + symbols.len(), symbol_size);
+        for (i, symbol) in symbols.iter().enumerate() {
+            d.get_mut(constraint_rows + i).copy_from_slice(symbol);
+        }
+        IntermediateSymbolDecoder::new_binary(
+            self.constraint_matrix(encoding_symbol_ids),
+            d,
+            self.source_symbols,
+            self.intermediate_symbols as usize,
+            0,
+        )
+        .execute()
+        .0
+    }
+
+    fn encode_into(
+        &self,
+        dest: &mut [u8],
+        intermediate_symbols: &SymbolSlab,
+        encoding_symbol_id: u32,
+    ) {
+        dest.fill(0);
+        self.lt_indices(encoding_symbol_id, |i| {
+            add_assign(dest, intermediate_symbols.get(i))
+        });
+    }
+}
+
+// Divides a source block into sub-blocks and concatenates their sub-symbols into symbols
+fn interleave_symbols(
+    block: &[u8],
+    source_symbols: usize,
+    sub_symbol_sizes: &[usize],
+) -> SymbolSlab {
+    let symbol_size = sub_symbol_sizes.iter().sum();
+    let mut symbols = SymbolSlab::with_zeros(source_symbols, symbol_size);
+    let mut block_offset = 0;
+    let mut symbol_offset = 0;
+    for &size in sub_symbol_sizes {
+        for i in 0..source_symbols {
+            let start = block_offset + i * size;
+            symbols.get_mut(i)[symbol_offset..symbol_offset + size]
+                .copy_from_slice(&block[start..start + size]);
+        }
+        block_offset += source_symbols * size;
+        symbol_offset += size;
+    }
+    symbols
+}
+
+// Inverse of interleave_symbols()
+fn deinterleave_symbols(symbols: &[&[u8]], sub_symbol_sizes: &[usize]) -> Vec<u8> {
+    let symbol_size: usize = sub_symbol_sizes.iter().sum();
+    let mut block = Vec::with_capacity(symbols.len() * symbol_size);
+    let mut symbol_offset = 0;
+    for &size in sub_symbol_sizes {
+        for symbol in symbols {
+            block.extend_from_slice(&symbol[symbol_offset..symbol_offset + size]);
+        }
+        symbol_offset += size;
+    }
+    block
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct R10SourceBlockEncoder {
+    source_block_number: u16,
+    parameters: BlockParameters,
+    source_symbols: SymbolSlab,
+    intermediate_symbols: SymbolSlab,
+}
+
+/// Systematic Raptor encoder, as specified in RFC 5053.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct R10Encoder {
+    config: R10ObjectTransmissionInformation,
+    blocks: Vec<R10SourceBlockEncoder>,
+}
+
+impl R10Encoder {
+    /// Encodes `data`, whose length must equal the transfer length of `config`.
+    pub fn new(data: &[u8], config: R10ObjectTransmissionInformation) -> R10Encoder {
+        assert_eq!(data.len() as u64, config.transfer_length());
+        let symbol_size = config.symbol_size() as usize;
+        let sub_symbol_sizes = config.sub_symbol_sizes();
+
+        let mut blocks = vec![];
+        let mut offset = 0;
+        for (source_block_number, source_symbols) in
+            config.source_block_lengths().into_iter().enumerate()
+        {
+            // Zero pad the last block if necessary
+            let end = offset + source_symbols as usize * symbol_size;
+            let mut block = Vec::from(&data[offset..end.min(data.len())]);
+            block.resize(end - offset, 0);
+            offset = end;
+
+            let parameters = BlockParameters::new(source_symbols);
+            let source_symbols =
+                interleave_symbols(&block, source_symbols as usize, &sub_symbol_sizes);
+            let encoding_symbol_ids: Vec<u32> = (0..parameters.source_symbols).collect();
+            let symbols: Vec<&[u8]> = (0..source_symbols.len())
+                .map(|i| source_symbols.get(i))
+                .collect();
+            let intermediate_symbols = parameters
+                .intermediate_symbols(&encoding_symbol_ids, &symbols, symbol_size)
+                .expect("systematic index does not yield an invertible constraint matrix");
+            blocks.push(R10SourceBlockEncoder {
+                source_block_number: source_block_number as u16,
+                parameters,
+                source_symbols,
+                intermediate_symbols,
+            });
+        }
+
+        R10Encoder { config, blocks }
+    }
+
+    pub fn get_config(&self) -> R10ObjectTransmissionInformation {
+        self.config
+    }
+
+    /// Returns the source packets of every block, followed by `repair_packets_per_block` repair
+    /// packets for each block. The Encoding Symbol ID is 16 bits, so at most 65536 - K repair
+    /// packets are generated for a block of K source symbols.
+    pub fn get_encoded_packets(&self, repair_packets_per_block: u32) -> Vec<R10EncodingPacket> {
+        let mut packets = vec![];
+        for block in self.blocks.iter() {
+            let source_symbols = block.parameters.source_symbols;
+            for esi in 0..source_symbols {
+                packets.push(R10EncodingPacket::new(
+                    R10PayloadId::new(block.source_block_number, esi as u16),
+                    block.source_symbols.get(esi as usize).to_vec(),
+                ));
+            }
+        }
+        for block in self.blocks.iter() {
+            let source_symbols = block.parameters.source_symbols;
+            let end_esi = source_symbols
+                .saturating_add(repair_packets_per_block)
+                .min(u16::MAX as u32 + 1);
+            for esi in source_symbols..end_esi {
+                let mut symbol = vec![0; self.config.symbol_size() as usize];
+                block
+                    .parameters
+                    .encode_into(&mut symbol, &block.intermediate_symbols, esi);
+                packets.push(R10EncodingPacket::new(
+                    R10PayloadId::new(block.source_block_number, esi as u16),
+                    symbol,
+                ));
+            }
+        }
+        packets
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct R10SourceBlockDecoder {
+    parameters: BlockParameters,
+    encoding_symbol_ids: Vec<u32>,
+    received_esi: Set<u32>,
+    symbols: Vec<Vec<u8>>,
+    received_source_symbols: u32,
+    // Number of symbols needed before the next attempt to solve the block
+    next_attempt: usize,
+    result: Option<Vec<u8>>,
+}
+
+impl R10SourceBlockDecoder {
+    fn new(parameters: BlockParameters) -> R10SourceBlockDecoder {
+        R10SourceBlockDecoder {
+            parameters,
+            encoding_symbol_ids: vec![],
+            received_esi: Set::new(),
+            symbols: vec![],
+            received_source_symbols: 0,
+            next_attempt: parameters.source_symbols as usize,
+            result: None,
+        }
+    }
+
+    fn add_symbol(
+        &mut self,
+        encoding_symbol_id: u32,
+        symbol: Vec<u8>,
+        config: &R10ObjectTransmissionInformation,
+    ) {
+        if self.result.is_some() || !self.received_esi.insert(encoding_symbol_id) {
+            return;
+        }
+        if encoding_symbol_id < self.parameters.source_symbols {
+            self.received_source_symbols += 1;
+        }
+        self.encoding_symbol_ids.push(encoding_symbol_id);
+        self.symbols.push(symbol);
+        // Every source symbol makes decoding trivial, so there is no need to wait for the next
+        // attempt
+        if self.symbols.len() < self.next_attempt
+            && self.received_source_symbols < self.parameters.source_symbols
+        {
+            return;
+        }
+
+        let symbol_size = config.symbol_size() as usize;
+        let mut source_symbols: Vec<Option<&[u8]>> =
+            vec![None; self.parameters.source_symbols as usize];
+        for (&esi, symbol) in self.encoding_symbol_ids.iter().zip(self.symbols.iter()) {
+            if esi < self.parameters.source_symbols {
+                source_symbols[esi as usize] = Some(symbol);
+            }
+        }
+        let mut rebuilt = vec![];
+        if self.received_source_symbols < self.parameters.source_symbols {
+            let symbols: Vec<&[u8]> = self.symbols.iter().map(|s| s.as_slice()).collect();
+            let Some(intermediate_symbols) = self.parameters.intermediate_symbols(
+                &self.encoding_symbol_ids,
+                &symbols,
+                symbol_size,
+            ) else {
+                // Each extra symbol only makes the matrix invertible with probability of about
+                // 1/2, so the gap between attempts doubles, keeping the number of times the
+                // block is solved logarithmic in the number of extra symbols received
+                let extra_symbols = self.symbols.len() - self.parameters.source_symbols as usize;
+                self.next_attempt = self.symbols.len() + extra_symbols + 1;
+                return;
+            };
+            for (esi, symbol) in source_symbols.iter().enumerate() {
+                if symbol.is_none() {
+                    let mut rebuilt_symbol = vec![0; symbol_size];
+                    self.parameters.encode_into(
+                        &mut rebuilt_symbol,
+                        &intermediate_symbols,
+                        esi as u32,
+                    );
+                    rebuilt.push(rebuilt_symbol);
+                }
+            }
+        }
+        let mut rebuilt = rebuilt.iter();
+        let symbols: Vec<&[u8]> = source_symbols
+            .iter()
+            .map(|symbol| symbol.unwrap_or_else(|| rebuilt.next().unwrap()))
+            .collect();
+        self.result = Some(deinterleave_symbols(&symbols, &config.sub_symbol_sizes()));
+        self.encoding_symbol_ids.clear();
+        self.received_esi.clear();
+        self.symbols.clear();
+    }
+}
+
+/// Decoder of objects encoded with Raptor, as specified in RFC 5053.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct R10Decoder {
+    config: R10ObjectTransmissionInformation,
+    blocks: Vec<R10SourceBlockDecoder>,
+}
+
+impl R10Decoder {
+    /// Panics if `config` is rejected by `R10ObjectTransmissionInformation::validate`.
+    pub fn new(config: R10ObjectTransmissionInformation) -> R10Decoder {
+        if let Err(err) = config.validate() {
+            panic!("{err}");
+        }
+        R10Decoder {
+            config,
+            blocks: config
+                .source_block_lengths()
+                .into_iter()
+                .map(|source_symbols| {
+                    R10SourceBlockDecoder::new(BlockParameters::new(source_symbols))
+                })
+                .collect(),
+        }
+    }
+
+    pub fn get_config(&self) -> R10ObjectTransmissionInformation {
+        self.config
+    }
+
+    /// Adds a packet, returning the object once every source block has been decoded.
+    ///
+    /// Panics if the packet does not belong to the object, see `try_decode`.
+    pub fn decode(&mut self, packet: R10EncodingPacket) -> Option<Vec<u8>> {
+        match self.try_decode(packet) {
+            Ok(result) => result,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Like `decode`, but rejects malformed packets instead of panicking.
+    pub fn try_decode(&mut self, packet: R10EncodingPacket) -> Result<Option<Vec<u8>>, R10Error> {
+        let (payload_id, symbol) = packet.split();
+        let block = self
+            .blocks
+            .get_mut(payload_id.source_block_number() as usize)
+            .ok_or(R10Error::InvalidSourceBlockNumber(
+                payload_id.source_block_number(),
+            ))?;
+        if symbol.len() != self.config.symbol_size() as usize {
+            return Err(R10Error::InvalidSymbolLength {
+                expected: self.config.symbol_size() as usize,
+                actual: symbol.len(),
+            });
+        }
+        block.add_symbol(payload_id.encoding_symbol_id() as u32, symbol, &self.config);
+
+        if self.blocks.iter().any(|block| block.result.is_none()) {
+            return Ok(None);
+        }
+        let mut result = Vec::with_capacity(self.config.transfer_length() as usize);
+        for block in self.blocks.iter() {
+            result.extend_from_slice(block.result.as_ref().unwrap());
+        }
+        result.truncate(self.config.transfer_length() as usize);
+        Ok(Some(result))
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use rand::seq::SliceRandom;
+    use std::vec::Vec;
+
+    use super::{BlockParameters, R10SourceBlockDecoder};
+    use crate::matrix::{BinaryMatrix, DenseBinaryMatrix};
+    use crate::octet::Octet;
+    use crate::{
+        R10_MAX_SOURCE_SYMBOLS_PER_BLOCK, R10_MIN_SOURCE_SYMBOLS_PER_BLOCK, R10Decoder, R10Encoder,
+        R10EncodingPacket, R10Error, R10ObjectTransmissionInformation,
+    };
+
+    // Rank of a matrix in GF(2), by Gaussian elimination over bit vector rows
+    fn rank(matrix: &DenseBinaryMatrix) -> usize {
+        let mut rows: Vec<u128> = vec![];
+        let mut basis: Vec<u128> = vec![];
+        for i in 0..matrix.height() {
+            let mut row = vec![0u128; matrix.width().div_ceil(128)];
+            for j in 0..matrix.width() {
+                if matrix.get(i, j) == Octet::one() {
+                    row[j / 128] |= 1 << (j % 128);
+                }
+            }
+            rows.extend(row);
+        }
+        let words = matrix.width().div_ceil(128);
+        for row in rows.chunks(words) {
+            let mut row = row.to_vec();
+            for pivot in basis.chunks(words) {
+                let column = pivot_column(pivot);
+                if (row[column / 128] >> (column % 128)) & 1 == 1 {
+                    row.iter_mut().zip(pivot).for_each(|(a, b)| *a ^= b);
+                }
+            }
+            if row.iter().any(|&word| word != 0) {
+                basis.extend(row);
+            }
+        }
+        basis.len() / words
+    }
+
+    fn pivot_column(row: &[u128]) -> usize {
+        let word = row.iter().position(|&word| word != 0).unwrap();
+        word * 128 + row[word].trailing_zeros() as usize
+    }
+
+    #[test]
+    fn systematic_indices() {
+        // Every systematic index must make the constraint matrix of the first K encoding symbols
+        // invertible
+        for k in R10_MIN_SOURCE_SYMBOLS_PER_BLOCK..=R10_MAX_SOURCE_SYMBOLS_PER_BLOCK {
+            let parameters = BlockParameters::new(k);
+            let encoding_symbol_ids: Vec<u32> = (0..k).collect();
+            let matrix = parameters.constraint_matrix(&encoding_symbol_ids);
+            assert_eq!(
+                rank(&matrix),
+                parameters.intermediate_symbols as usize,
+                "K = {k}"
+            );
+        }
+    }
+
+    #[test]
+    fn parameters() {
+        // K = 4: X = 4, S = next_prime(1 + 4) = 5, H = 5 since choose(5, 3) = 10 >= 9
+        let parameters = BlockParameters::new(4);
+        assert_eq!(parameters.ldpc_symbols, 5);
+        assert_eq!(parameters.half_symbols, 5);
+        assert_eq!(parameters.intermediate_symbols, 14);
+        assert_eq!(parameters.intermediate_symbols_prime, 17);
+        assert_eq!(parameters.systematic_index, 18);
+    }
+
+    #[test]
+    fn oti_serialization() {
+        let config = R10ObjectTransmissionInformation::new(1_000_000, 1024, 5, 2, 4);
+        assert_eq!(
+            config.serialize(),
+            [0, 0, 0, 0x0F, 0x42, 0x40, 0, 0, 0x04, 0x00, 0, 5, 2, 4]
+        );
+        assert_eq!(
+            R10ObjectTransmissionInformation::try_deserialize(&config.serialize()),
+            Ok(config)
+        );
+        assert_eq!(
+            R10ObjectTransmissionInformation::try_new(1_000_000, 1024, 1, 1, 4),
+            Err(R10Error::UnsupportedSourceBlockLength(977))
+        );
+        assert_eq!(
+            R10ObjectTransmissionInformation::try_new(1000, 100, 1, 1, 8),
+            Err(R10Error::InvalidParameters)
+        );
+        assert_eq!(
+            R10ObjectTransmissionInformation::try_new(10, 4, 1, 1, 4),
+            Err(R10Error::UnsupportedSourceBlockLength(3))
+        );
+    }
+
+    #[test]
+    fn systematic_encoding() {
+        let data: Vec<u8> = (0..1000).map(|_| rand::rng().random()).collect();
+        let config = R10ObjectTransmissionInformation::new(1000, 16, 1, 1, 4);
+        let encoder = R10Encoder::new(&data, config);
+        let packets = encoder.get_encoded_packets(0);
+        assert_eq!(packets.len(), 63);
+        for packet in packets.iter().take(62) {
+            let esi = packet.payload_id().encoding_symbol_id() as usize;
+            assert_eq!(packet.data(), &data[esi * 16..(esi + 1) * 16]);
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for _ in 0..3 {
+            let length: u64 = rand::rng().random_range(2_000..20_000);
+            let data: Vec<u8> = (0..length).map(|_| rand::rng().random()).collect();
+            let source_blocks = length.div_ceil(64 * 200) as u16;
+            let config = R10ObjectTransmissionInformation::new(length, 64, source_blocks, 2, 8);
+            let encoder = R10Encoder::new(&data, config);
+
+            let mut packets = encoder.get_encoded_packets(30);
+            packets.shuffle(&mut rand::rng());
+            // Lose 10% of the packets
+            packets.truncate(packets.len() * 9 / 10);
+
+            let mut decoder = R10Decoder::new(config);
+            let mut result = None;
+            for packet in packets {
+                let packet = R10EncodingPacket::try_deserialize(&packet.serialize()).unwrap();
+                if let Some(decoded) = decoder.decode(packet) {
+                    result = Some(decoded);
+                    break;
+                }
+            }
+            assert_eq!(result, Some(data));
+        }
+    }
+
+    #[test]
+    fn repair_packets_limited_to_esi_space() {
+        let data = [1, 2, 3, 4];
+        let config = R10ObjectTransmissionInformation::new(data.len() as u64, 1, 1, 1, 1);
+        let packets = R10Encoder::new(&data, config).get_encoded_packets(u32::MAX);
+        assert_eq!(packets.len(), u16::MAX as usize + 1);
+        assert_eq!(
+            packets.last().unwrap().payload_id().encoding_symbol_id(),
+            u16::MAX
+        );
+    }
+
+    #[test]
+    fn reject_invalid_packets() {
+        let config = R10ObjectTransmissionInformation::new(1000, 16, 1, 1, 4);
+        let mut decoder = R10Decoder::new(config);
+        let packet = R10EncodingPacket::deserialize(&[0, 1, 0, 0, 1, 2, 3]);
+        assert_eq!(
+            decoder.try_decode(packet),
+            Err(R10Error::InvalidSourceBlockNumber(1))
+        );
+        let packet = R10EncodingPacket::deserialize(&[0, 0, 0, 0, 1, 2, 3]);
+        assert_eq!(
+            decoder.try_decode(packet),
+            Err(R10Error::InvalidSymbolLength {
+                expected: 16,
+                actual: 3
+            })
+        );
+        assert_eq!(
+            R10EncodingPacket::try_deserialize(&[0, 0, 1]),
+            Err(R10Error::TruncatedPacket(3))
+        );
+        assert_eq!(
+            R10EncodingPacket::try_deserialize(&[0, 2, 0, 5, 9]).unwrap(),
+            R10EncodingPacket::deserialize(&[0, 2, 0, 5, 9])
+        );
+    }
+
+    #[test]
+    fn solve_attempts_back_off() {
+        let k = 4;
+        let parameters = BlockParameters::new(k);
+        let config = R10ObjectTransmissionInformation::new(k as u64 * 4, 4, 1, 1, 4);
+        let data: Vec<u8> = (0..k * 4).map(|i| i as u8).collect();
+        let encoder = R10Encoder::new(&data, config);
+        let packets = encoder.get_encoded_packets(1000);
+
+        // Find K repair symbols which do not determine the intermediate symbols
+        let repair = &packets[k as usize..];
+        let start = (0..repair.len() - k as usize)
+            .find(|&start| {
+                let encoding_symbol_ids: Vec<u32> = repair[start..start + k as usize]
+                    .iter()
+                    .map(|packet| packet.payload_id().encoding_symbol_id() as u32)
+                    .collect();
+                rank(&parameters.constraint_matrix(&encoding_symbol_ids))
+                    < parameters.intermediate_symbols as usize
+            })
+            .unwrap();
+
+        let mut decoder = R10SourceBlockDecoder::new(parameters);
+        let mut attempts = vec![];
+        for (received, packet) in repair[start..].iter().enumerate() {
+            let next_attempt = decoder.next_attempt;
+            decoder.add_symbol(
+                packet.payload_id().encoding_symbol_id() as u32,
+                packet.data().to_vec(),
+                &config,
+            );
+            if received + 1 == next_attempt {
+                attempts.push(next_attempt);
+            } else {
+                assert_eq!(decoder.next_attempt, next_attempt);
+            }
+            if decoder.result.is_some() {
+                break;
+            }
+        }
+        assert_eq!(decoder.result, Some(data));
+        // The block is solved with K symbols, and then with a doubling number of extra symbols
+        let k = k as usize;
+        assert_eq!(&attempts[..2], &[k, k + 1]);
+        for (i, attempt) in attempts.iter().enumerate() {
+            assert_eq!(*attempt, k + (1 << i) - 1);
+        }
+    }
+
+    #[test]
+    fn systematic_index_samples() {
+        // J(K) entries from the table in RFC 5053 section 5.7
+        for (k, j) in [(4, 18), (5, 14), (6, 61), (13, 1), (31, 0), (76, 100)] {
+            assert_eq!(BlockParameters::new(k).systematic_index, j);
+        }
+    }
+
+    #[test]
+    fn encoded_symbol_vectors() {
+        // RFC 5053 publishes no test vectors, so these pin the repair symbols produced for a fixed
+        // input and guard against regressions in the tuple generator and intermediate symbols
+        let cases: [(u32, u16, [&[u8]; 4]); 3] = [
+            (
+                4,
+                4,
+                [
+                    &[36, 100, 100, 100],
+                    &[56, 72, 88, 72],
+                    &[108, 28, 44, 60],
+                    &[84, 84, 116, 116],
+                ],
+            ),
+            (
+                10,
+                8,
+                [
+                    &[227, 170, 177, 248, 255, 6, 77, 84],
+                    &[56, 8, 24, 72, 72, 120, 8, 24],
+                    &[24, 104, 184, 168, 168, 152, 168, 120],
+                    &[139, 114, 153, 192, 199, 46, 21, 60],
+                ],
+            ),
+            (213, 2, [&[189, 132], &[51, 58], &[40, 56], &[127, 38]]),
+        ];
+        for (k, symbol_size, expected) in cases {
+            let data: Vec<u8> = (0..k * symbol_size as u32)
+                .map(|i| (i * 7 + 3) as u8)
+                .collect();
+            let config =
+                R10ObjectTransmissionInformation::new(data.len() as u64, symbol_size, 1, 1, 1);
+            let packets = R10Encoder::new(&data, config).get_encoded_packets(4);
+            assert_eq!(packets.len(), k as usize + 4);
+            for (i, packet) in packets[k as usize..].iter().enumerate() {
+                assert_eq!(
+                    u32::from(packet.payload_id().encoding_symbol_id()),
+                    k + i as u32
+                );
+                assert_eq!(packet.data(), expected[i]);
+            }
+        }
+    }
+}
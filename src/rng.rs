@@ -240,3 +240,12 @@ where
 
     (V0[x0 as usize] ^ V1[x1 as usize] ^ V2[x2 as usize] ^ V3[x3 as usize]) % m
 }
+
+// Rand[X, i, m] of Raptor, as defined in RFC 5053, which only uses the first two tables
+pub fn r10_rand(x: u32, i: u32, m: u32) -> u32 {
+    assert!(m > 0);
+    let x0 = (x + i) % 256;
+    let x1 = ((x >> 8) + i) % 256;
+
+    (V0[x0 as usize] ^ V1[x1 as usize]) % m
+}
@@ -0,0 +1,439 @@
+use std::io::{self, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::iter;
+use std::vec::Vec;
+
+use crate::base::{EncodingPacket, ObjectTransmissionInformation};
+use crate::decoder::{DecodeError, SourceBlockDecoder};
+use crate::encoder::{SourceBlockEncoder, calculate_block_offsets_for_length};
+
+/// Encodes an object read from a `Read` one source block at a time.
+///
+/// Only the source block currently being encoded is held in memory, which makes it possible to
+/// encode objects much larger than the available memory.
+///
+/// A failed read may have consumed part of a source block, after which the position of the
+/// reader no longer matches the next block. The encoder therefore fails every later call with an
+/// error of the same kind, rather than encoding misaligned data.
+pub struct StreamingEncoder<R: Read> {
+    reader: R,
+    config: ObjectTransmissionInformation,
+    block_offsets: Vec<(usize, usize)>,
+    next_block: usize,
+    failed: Option<ErrorKind>,
+}
+
+impl<R: Read> StreamingEncoder<R> {
+    /// Creates an encoder which reads exactly `config.transfer_length()` bytes from `reader`.
+    pub fn new(reader: R, config: ObjectTransmissionInformation) -> StreamingEncoder<R> {
+        let block_offsets =
+            calculate_block_offsets_for_length(config.transfer_length() as usize, &config);
+        StreamingEncoder {
+            reader,
+            config,
+            block_offsets,
+            next_block: 0,
+            failed: None,
+        }
+    }
+
+    pub fn with_defaults(
+        reader: R,
+        transfer_length: u64,
+        maximum_transmission_unit: u16,
+    ) -> StreamingEncoder<R> {
+        let config = ObjectTransmissionInformation::with_defaults(
+            transfer_length,
+            maximum_transmission_unit,
+        );
+        StreamingEncoder::new(reader, config)
+    }
+
+    pub fn get_config(&self) -> ObjectTransmissionInformation {
+        self.config
+    }
+
+    /// Number of source blocks which have not been read yet.
+    pub fn remaining_blocks(&self) -> usize {
+        self.block_offsets.len() - self.next_block
+    }
+
+    /// Reads the next source block and returns an encoder for it, or `None` once every block
+    /// has been read. Once a read has failed, every later call fails too.
+    pub fn next_block_encoder(&mut self) -> io::Result<Option<SourceBlockEncoder>> {
+        if let Some(kind) = self.failed {
+            return Err(io::Error::new(
+                kind,
+                format!(
+                    "reading source block {} failed previously, so the reader position is unknown",
+                    self.next_block
+                ),
+            ));
+        }
+        let Some(&(start, end)) = self.block_offsets.get(self.next_block) else {
+            return Ok(None);
+        };
+
+        // Zero pad if necessary
+        let transfer_length = self.config.transfer_length() as usize;
+        let mut block = vec![0; end - start];
+        let available = end.min(transfer_length).saturating_sub(start);
+        if let Err(err) = self.reader.read_exact(&mut block[..available]) {
+            self.failed = Some(err.kind());
+            return Err(err);
+        }
+
+        let encoder = SourceBlockEncoder::new(self.next_block as u8, &self.config, &block);
+        self.next_block += 1;
+        Ok(Some(encoder))
+    }
+
+    /// Reads the next source block and returns its source packets, followed by
+    /// `repair_packets_per_block` repair packets. Returns `None` once every block has been read.
+    pub fn next_block_packets(
+        &mut self,
+        repair_packets_per_block: u32,
+    ) -> io::Result<Option<Vec<EncodingPacket>>> {
+        Ok(self.next_block_encoder()?.map(|encoder| {
+            let mut packets = encoder.source_packets();
+            packets.extend(encoder.repair_packets(0, repair_packets_per_block));
+            packets
+        }))
+    }
+
+    /// Consumes the encoder, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read + Seek> StreamingEncoder<R> {
+    /// Creates an encoder for the bytes between the current position of `reader` and its end.
+    pub fn from_seekable(
+        mut reader: R,
+        maximum_transmission_unit: u16,
+    ) -> io::Result<StreamingEncoder<R>> {
+        let position = reader.stream_position()?;
+        let end = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(position))?;
+        Ok(StreamingEncoder::with_defaults(
+            reader,
+            end - position,
+            maximum_transmission_unit,
+        ))
+    }
+}
+
+/// Decodes an object one source block at a time, handing each block off as soon as it has been
+/// recovered.
+///
+/// Unlike `Decoder`, decoded blocks are not retained. The state of a block is allocated when its
+/// first packet arrives and freed once the block has been written, so memory use is bounded by
+/// the number of blocks being received concurrently. A block which could not be written is kept
+/// until a later write succeeds.
+pub struct StreamingDecoder {
+    config: ObjectTransmissionInformation,
+    block_offsets: Vec<(usize, usize)>,
+    block_decoders: Vec<Option<SourceBlockDecoder>>,
+    // Blocks which have been decoded, but not yet written successfully
+    pending_blocks: Vec<Option<Vec<u8>>>,
+    written_blocks: Vec<bool>,
+    remaining_blocks: usize,
+}
+
+impl StreamingDecoder {
+    pub fn new(config: ObjectTransmissionInformation) -> StreamingDecoder {
+        let block_offsets =
+            calculate_block_offsets_for_length(config.transfer_length() as usize, &config);
+        let blocks = block_offsets.len();
+        StreamingDecoder {
+            config,
+            block_offsets,
+            block_decoders: vec![None; blocks],
+            pending_blocks: vec![None; blocks],
+            written_blocks: vec![false; blocks],
+            remaining_blocks: blocks,
+        }
+    }
+
+    pub fn get_config(&self) -> ObjectTransmissionInformation {
+        self.config
+    }
+
+    /// Returns true once every source block has been written.
+    pub fn is_complete(&self) -> bool {
+        self.remaining_blocks == 0
+    }
+
+    /// Adds a packet, and writes its source block to `writer` at the block's offset within the
+    /// object if the packet completes it.
+    ///
+    /// Returns true once every source block has been written.
+    pub fn decode_to<W: Write + Seek>(
+        &mut self,
+        packet: EncodingPacket,
+        writer: &mut W,
+    ) -> io::Result<bool> {
+        self.decode_with(packet, |offset, data| {
+            writer.seek(SeekFrom::Start(offset))?;
+            writer.write_all(data)
+        })
+    }
+
+    /// Adds a packet, and calls `write_block` with the byte offset and contents of its source
+    /// block if the packet completes it. Padding beyond the transfer length is not included.
+    ///
+    /// Packets for blocks which have already been decoded are ignored. Malformed packets are
+    /// rejected with an error of kind `InvalidData`, wrapping a `DecodeError`.
+    ///
+    /// If `write_block` fails, the error is returned and the decoded block is kept, so that its
+    /// write is retried by the next call, or by `write_pending_with`.
+    ///
+    /// Returns true once every source block has been written.
+    pub fn decode_with<F: FnMut(u64, &[u8]) -> io::Result<()>>(
+        &mut self,
+        packet: EncodingPacket,
+        write_block: F,
+    ) -> io::Result<bool> {
+        let source_block_number = packet.payload_id().source_block_number();
+        let block_number = source_block_number as usize;
+        if block_number >= self.block_offsets.len() {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                DecodeError::InvalidSourceBlockNumber(source_block_number),
+            ));
+        }
+
+        if !self.written_blocks[block_number] && self.pending_blocks[block_number].is_none() {
+            let (start, end) = self.block_offsets[block_number];
+            let config = &self.config;
+            let block_decoder = self.block_decoders[block_number].get_or_insert_with(|| {
+                SourceBlockDecoder::new(source_block_number, config, (end - start) as u64)
+            });
+            let decoded = block_decoder
+                .try_decode(iter::once(packet))
+                .map_err(|err| io::Error::new(ErrorKind::InvalidData, err))?;
+
+            if let Some(mut block) = decoded {
+                block.truncate(end.min(self.config.transfer_length() as usize) - start);
+                self.block_decoders[block_number] = None;
+                self.pending_blocks[block_number] = Some(block);
+            }
+        }
+        self.write_pending_with(write_block)
+    }
+
+    /// Calls `write_block` for every block which has been decoded, but whose previous write
+    /// failed, and stops at the first error.
+    ///
+    /// Returns true once every source block has been written.
+    pub fn write_pending_with<F: FnMut(u64, &[u8]) -> io::Result<()>>(
+        &mut self,
+        mut write_block: F,
+    ) -> io::Result<bool> {
+        for (block_number, pending) in self.pending_blocks.iter_mut().enumerate() {
+            if let Some(block) = pending {
+                write_block(self.block_offsets[block_number].0 as u64, block)?;
+                *pending = None;
+                self.written_blocks[block_number] = true;
+                self.remaining_blocks -= 1;
+            }
+        }
+        Ok(self.is_complete())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use std::io::{self, Cursor, ErrorKind};
+    use std::vec::Vec;
+
+    use rand::seq::SliceRandom;
+
+    use crate::encoder::Encoder as EncoderNative;
+    use crate::{
+        EncodingPacket, ObjectTransmissionInformation, PayloadId, StreamingDecoder,
+        StreamingEncoder,
+    };
+
+    fn gen_test_data(size: usize) -> Vec<u8> {
+        let mut data: Vec<u8> = vec![0; size];
+        for byte in data.iter_mut() {
+            *byte = rand::rng().random();
+        }
+        data
+    }
+
+    #[test]
+    fn matches_in_memory_encoder() {
+        let data = gen_test_data(100_000);
+        // Force several source blocks, the last of which needs zero padding
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 96, 7, 1, 8);
+        let expected = EncoderNative::new(&data, config).get_encoded_packets(3);
+
+        let mut encoder = StreamingEncoder::new(Cursor::new(&data), config);
+        assert_eq!(encoder.remaining_blocks(), 7);
+        let mut packets = vec![];
+        while let Some(block_packets) = encoder.next_block_packets(3).unwrap() {
+            packets.extend(block_packets);
+        }
+        assert_eq!(encoder.remaining_blocks(), 0);
+        assert_eq!(packets, expected);
+    }
+
+    #[test]
+    fn from_seekable() {
+        let data = gen_test_data(10_000);
+        let expected = EncoderNative::with_defaults(&data, 1400).get_encoded_packets(2);
+
+        let mut encoder = StreamingEncoder::from_seekable(Cursor::new(&data), 1400).unwrap();
+        assert_eq!(encoder.get_config().transfer_length(), data.len() as u64);
+        let mut packets = vec![];
+        while let Some(block_packets) = encoder.next_block_packets(2).unwrap() {
+            packets.extend(block_packets);
+        }
+        assert_eq!(packets, expected);
+    }
+
+    #[test]
+    fn truncated_reader() {
+        let data = gen_test_data(10_000);
+        let mut encoder =
+            StreamingEncoder::with_defaults(Cursor::new(&data[..5000]), data.len() as u64, 1400);
+        let error = encoder.next_block_packets(0).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    // Fails once, after returning `fail_after` bytes, and then reads normally
+    struct FailOnce<'a> {
+        data: &'a [u8],
+        fail_after: usize,
+    }
+
+    impl io::Read for FailOnce<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.fail_after == 0 {
+                self.fail_after = usize::MAX;
+                return Err(io::Error::other("transient failure"));
+            }
+            let length = buf.len().min(self.data.len()).min(self.fail_after);
+            buf[..length].copy_from_slice(&self.data[..length]);
+            self.data = &self.data[length..];
+            self.fail_after = self.fail_after.saturating_sub(length);
+            Ok(length)
+        }
+    }
+
+    #[test]
+    fn retry_after_failed_read() {
+        let data = gen_test_data(100_000);
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 96, 7, 1, 8);
+        let reader = FailOnce {
+            data: &data,
+            fail_after: 100,
+        };
+        let mut encoder = StreamingEncoder::new(reader, config);
+        let error = encoder.next_block_packets(0).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Other);
+
+        // The reader has recovered, but has lost its place in the first block
+        for _ in 0..2 {
+            let error = encoder.next_block_packets(0).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::Other);
+        }
+        assert_eq!(encoder.remaining_blocks(), 7);
+    }
+
+    #[test]
+    fn decode_to_writer() {
+        let data = gen_test_data(100_000);
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 96, 7, 1, 8);
+        let encoder = EncoderNative::new(&data, config);
+        let mut packets = encoder.get_encoded_packets(10);
+        packets.shuffle(&mut rand::rng());
+        // Erase 10 packets at random
+        let length = packets.len();
+        packets.truncate(length - 10);
+
+        let mut decoder = StreamingDecoder::new(config);
+        let mut output = Cursor::new(vec![]);
+        let mut complete = false;
+        for packet in packets {
+            complete = decoder.decode_to(packet, &mut output).unwrap();
+        }
+        assert!(complete);
+        assert!(decoder.is_complete());
+        assert_eq!(output.into_inner(), data);
+    }
+
+    #[test]
+    fn decode_with_callback() {
+        let data = gen_test_data(100_000);
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 96, 7, 1, 8);
+        let encoder = EncoderNative::new(&data, config);
+
+        let mut decoder = StreamingDecoder::new(config);
+        let mut blocks = vec![];
+        for packet in encoder.get_encoded_packets(0).into_iter().rev() {
+            decoder
+                .decode_with(packet, |offset, block| {
+                    blocks.push((offset as usize, block.to_vec()));
+                    Ok(())
+                })
+                .unwrap();
+        }
+        assert!(decoder.is_complete());
+        assert_eq!(blocks.len(), config.source_blocks() as usize);
+        for (offset, block) in blocks {
+            assert_eq!(&data[offset..offset + block.len()], &block[..]);
+        }
+    }
+
+    #[test]
+    fn retry_failed_write() {
+        let data = gen_test_data(100_000);
+        let config = ObjectTransmissionInformation::new(data.len() as u64, 96, 7, 1, 8);
+        let encoder = EncoderNative::new(&data, config);
+        let mut packets = encoder.get_encoded_packets(0).into_iter();
+
+        let mut decoder = StreamingDecoder::new(config);
+        let mut output = vec![0; data.len()];
+        let mut write_block = |offset: u64, block: &[u8]| {
+            let offset = offset as usize;
+            output[offset..offset + block.len()].copy_from_slice(block);
+            Ok(())
+        };
+        // Fail the write of the first block
+        let mut failed = false;
+        for packet in packets.by_ref() {
+            let result = decoder.decode_with(packet, |_, _| {
+                Err(io::Error::new(ErrorKind::StorageFull, "full"))
+            });
+            if result.is_err() {
+                failed = true;
+                break;
+            }
+        }
+        assert!(failed);
+        assert!(!decoder.is_complete());
+
+        // The block is kept and written along with the next packet
+        for packet in packets {
+            decoder.decode_with(packet, &mut write_block).unwrap();
+        }
+        assert!(decoder.is_complete());
+        assert_eq!(output, data);
+    }
+
+    #[test]
+    fn decode_rejects_invalid_block() {
+        let data = gen_test_data(10_000);
+        let encoder = EncoderNative::with_defaults(&data, 1400);
+        let mut decoder = StreamingDecoder::new(encoder.get_config());
+        let packet = EncodingPacket::new(PayloadId::new(9, 0), vec![0; 1400]);
+        let error = decoder
+            .decode_to(packet, &mut Cursor::new(vec![]))
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+}
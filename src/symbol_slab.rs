@@ -86,6 +86,12 @@ impl SymbolSlab {
         self.count
     }
 
+    #[inline]
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
     #[inline]
     pub fn symbol_size(&self) -> usize {
         self.symbol_size
@@ -177,6 +183,38 @@ impl SymbolSlab {
         self.data[start..start + source.len()].copy_from_slice(source);
     }
 
+    /// Borrow one view per `[start, end)` byte range, each holding that range of every symbol,
+    /// so that the ranges can be operated on concurrently without copying. The reorder mapping,
+    /// if any, is carried over to every view.
+    #[cfg(feature = "parallel")]
+    pub fn column_views_mut(&mut self, ranges: &[(usize, usize)]) -> Vec<ColumnsMut<'_>> {
+        let mut views: Vec<ColumnsMut> = ranges
+            .iter()
+            .map(|_| ColumnsMut {
+                symbols: Vec::with_capacity(self.count),
+                mapping: self.mapping.clone(),
+            })
+            .collect();
+        for mut symbol in self.data.chunks_exact_mut(self.symbol_size) {
+            let mut offset = 0;
+            for (view, &(start, end)) in views.iter_mut().zip(ranges.iter()) {
+                assert!(start >= offset && end >= start, "ranges must be ascending");
+                let (_, rest) = symbol.split_at_mut(start - offset);
+                let (columns, rest) = rest.split_at_mut(end - start);
+                view.symbols.push(columns);
+                symbol = rest;
+                offset = end;
+            }
+        }
+        views
+    }
+
+    /// Replace the reorder mapping, e.g. with the one reached by a `ColumnsMut` view.
+    #[cfg(feature = "parallel")]
+    pub fn set_mapping(&mut self, mapping: Option<Vec<usize>>) {
+        self.mapping = mapping;
+    }
+
     /// Create a new slab by gathering symbols at the given indices from self.
     /// The new slab has `indices.len()` symbols.
     #[allow(dead_code)]
@@ -198,6 +236,67 @@ impl SymbolSlab {
     }
 }
 
+/// Mutable view of a range of bytes of every symbol in a `SymbolSlab`, with its own copy of the
+/// reorder mapping. See `SymbolSlab::column_views_mut`.
+#[cfg(feature = "parallel")]
+pub struct ColumnsMut<'a> {
+    symbols: Vec<&'a mut [u8]>,
+    mapping: Option<Vec<usize>>,
+}
+
+#[cfg(feature = "parallel")]
+impl ColumnsMut<'_> {
+    #[inline(always)]
+    fn physical_index(&self, i: usize) -> usize {
+        self.mapping.as_ref().map_or(i, |m| m[i])
+    }
+
+    #[inline]
+    fn get_pair_mut(&mut self, dest: usize, src: usize) -> (&mut [u8], &[u8]) {
+        let dest = self.physical_index(dest);
+        let src = self.physical_index(src);
+        assert_ne!(dest, src, "dest and src must differ");
+        if dest < src {
+            let (head, tail) = self.symbols.split_at_mut(src);
+            (&mut *head[dest], &*tail[0])
+        } else {
+            let (head, tail) = self.symbols.split_at_mut(dest);
+            (&mut *tail[0], &*head[src])
+        }
+    }
+
+    /// `dest[i] += src[i]` (GF(2) XOR)
+    #[inline]
+    pub fn add_assign(&mut self, dest: usize, src: usize) {
+        let (d, s) = self.get_pair_mut(dest, src);
+        add_assign(d, s);
+    }
+
+    /// `dest[i] *= scalar` (GF(256) multiply)
+    #[inline]
+    pub fn mulassign_scalar(&mut self, dest: usize, scalar: &Octet) {
+        let dest = self.physical_index(dest);
+        mulassign_scalar(self.symbols[dest], scalar);
+    }
+
+    /// `dest[i] += src[i] * scalar` (GF(256) fused multiply-add)
+    #[inline]
+    pub fn fma(&mut self, dest: usize, src: usize, scalar: &Octet) {
+        let (d, s) = self.get_pair_mut(dest, src);
+        fused_addassign_mul_scalar(d, s, scalar);
+    }
+
+    /// Set a virtual reorder mapping: logical index `i` maps to physical index `order[i]`.
+    pub fn set_reorder(&mut self, order: Vec<usize>) {
+        self.mapping = Some(order);
+    }
+
+    /// The reorder mapping reached after the operations applied to this view.
+    pub fn into_mapping(self) -> Option<Vec<usize>> {
+        self.mapping
+    }
+}
+
 #[cfg(feature = "std")]
 #[cfg(test)]
 mod tests {
@@ -1,3 +1,12 @@
+#[cfg(feature = "std")]
+use std::{string::String, vec::Vec};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+#[cfg(feature = "parallel")]
+use std::thread;
+
 // Get two non-overlapping ranges starting at i & j, both with length len
 #[inline]
 pub fn get_both_ranges<T>(
@@ -47,3 +56,193 @@ pub fn int_div_ceil(num: u64, denom: u64) -> u32 {
         (num / denom + 1) as u32
     }
 }
+
+// Table for a reflected CRC-32 with the given (reversed) polynomial
+const fn crc32_table(polynomial: u32) -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ polynomial
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+static CRC32_TABLE: [u32; 256] = crc32_table(0xEDB88320);
+static CRC32C_TABLE: [u32; 256] = crc32_table(0x82F63B78);
+
+fn crc32_with_table(table: &[u32; 256], crc: u32, data: &[u8]) -> u32 {
+    let mut crc = !crc;
+    for &byte in data {
+        crc = table[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+// CRC-32 (IEEE 802.3), as used by zlib and PNG
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    crc32_with_table(&CRC32_TABLE, 0, data)
+}
+
+// CRC-32C (Castagnoli), as used by iSCSI and SCTP
+pub fn crc32c(data: &[u8]) -> u32 {
+    crc32_with_table(&CRC32C_TABLE, 0, data)
+}
+
+// Extends the CRC-32C of some data to cover `data` appended to it, so that
+// crc32c_extend(crc32c(a), b) == crc32c(a ++ b)
+#[cfg(feature = "std")]
+pub fn crc32c_extend(crc: u32, data: &[u8]) -> u32 {
+    crc32_with_table(&CRC32C_TABLE, crc, data)
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// Base64 with padding, as defined in RFC 4648 section 4
+pub fn base64_encode(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bits = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, byte)| acc | (*byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64_ALPHABET[(bits >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+// Returns None unless encoded is canonical padded base64
+pub fn base64_decode(encoded: &str) -> Option<Vec<u8>> {
+    let encoded = encoded.as_bytes();
+    if !encoded.len().is_multiple_of(4) {
+        return None;
+    }
+    let mut decoded = Vec::with_capacity(encoded.len() / 4 * 3);
+    for (index, chunk) in encoded.chunks(4).enumerate() {
+        let last = index == encoded.len() / 4 - 1;
+        let padding = chunk.iter().rev().take_while(|c| **c == b'=').count();
+        if padding > 2 || (padding > 0 && !last) {
+            return None;
+        }
+        let mut bits = 0u32;
+        for (i, c) in chunk[..4 - padding].iter().enumerate() {
+            let value = BASE64_ALPHABET.iter().position(|a| a == c)? as u32;
+            bits |= value << (18 - 6 * i);
+        }
+        let bytes = 3 - padding;
+        // Reject non-zero bits in the padding
+        if bits & ((1 << (8 * (3 - bytes))) - 1) != 0 {
+            return None;
+        }
+        decoded.extend_from_slice(&bits.to_be_bytes()[1..1 + bytes]);
+    }
+    Some(decoded)
+}
+
+// Applies `f` to every element of `items`, spreading the work across up to one thread per CPU.
+// The results are returned in the same order as `items`
+#[cfg(not(feature = "parallel"))]
+pub fn parallel_map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(usize, &T) -> R + Sync,
+{
+    items.iter().enumerate().map(|(i, x)| f(i, x)).collect()
+}
+
+// Applies `f` to every element of `items`, spreading the work across up to one thread per CPU.
+// The results are returned in the same order as `items`
+#[cfg(feature = "parallel")]
+pub fn parallel_map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(usize, &T) -> R + Sync,
+{
+    let threads = thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(items.len());
+    if threads <= 1 {
+        return items.iter().enumerate().map(|(i, x)| f(i, x)).collect();
+    }
+
+    let chunk_size = items.len().div_ceil(threads);
+    let f = &f;
+    thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .chunks(chunk_size)
+            .enumerate()
+            .map(|(chunk_index, chunk)| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .enumerate()
+                        .map(|(i, x)| f(chunk_index * chunk_size + i, x))
+                        .collect::<Vec<R>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+#[cfg(feature = "std")]
+#[cfg(test)]
+mod tests {
+    use crate::util::{base64_decode, base64_encode, crc32, crc32c, crc32c_extend};
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF43926);
+    }
+
+    #[test]
+    fn crc32c_check_value() {
+        assert_eq!(crc32c(b""), 0);
+        assert_eq!(crc32c(b"123456789"), 0xE3069283);
+        assert_eq!(crc32c_extend(crc32c(b"1234"), b"56789"), 0xE3069283);
+    }
+
+    #[test]
+    fn base64_test_vectors() {
+        // RFC 4648 section 10
+        for (data, encoded) in [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ] {
+            assert_eq!(base64_encode(data.as_bytes()), encoded);
+            assert_eq!(base64_decode(encoded), Some(data.as_bytes().to_vec()));
+        }
+        for invalid in ["Zg", "Zg=a", "Zh==", "Z===", "Zg==Zg==", "Zm9*"] {
+            assert_eq!(base64_decode(invalid), None);
+        }
+    }
+}
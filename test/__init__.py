@@ -33,3 +33,22 @@ class EncoderDecoderTestCase(TestCase):
                 break
 
         self.assertEqual(decoded_data, data)
+
+    def test_decoder_ignores_extra_packets(self):
+        from raptorq import Encoder, Decoder
+
+        data = os.urandom(100_000)
+        encoder = Encoder.with_defaults(data, 512)
+        packets = encoder.get_encoded_packets(10)
+
+        decoded_data = None
+        decoder = Decoder.with_defaults(len(data), 512)
+        # Packets which arrive after their block has been decoded are ignored
+        for packet in packets:
+            result = decoder.decode(packet)
+            if result is not None:
+                decoded_data = result
+
+        self.assertEqual(decoded_data, data)
+        with self.assertRaises(ValueError):
+            decoder.decode(b"\x00\x00")
@@ -0,0 +1,119 @@
+use rand::Rng;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn raptorq(args: &[&Path]) {
+    let status = Command::new(env!("CARGO_BIN_EXE_raptorq"))
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "raptorq {args:?} failed");
+}
+
+// Runs the binary, expecting it to fail with an error message rather than a panic, and returns
+// the message
+fn raptorq_error(args: &[&Path]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_raptorq"))
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(!output.status.success(), "raptorq {args:?} succeeded");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("error: "), "raptorq {args:?}: {stderr}");
+    stderr
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("raptorq-cli-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write_input(dir: &Path) -> (PathBuf, Vec<u8>) {
+    let data: Vec<u8> = (0..200_000).map(|_| rand::rng().random()).collect();
+    let input = dir.join("input");
+    fs::write(&input, &data).unwrap();
+    (input, data)
+}
+
+#[test]
+fn container_round_trip() {
+    let dir = temp_dir("container");
+    let (input, data) = write_input(&dir);
+    let encoded = dir.join("encoded.rqc");
+    let output = dir.join("output");
+
+    // A small memory limit, so that the object is split into several source blocks
+    raptorq(&[
+        Path::new("encode"),
+        Path::new("--single"),
+        Path::new("--mtu"),
+        Path::new("100"),
+        Path::new("--memory-limit"),
+        Path::new("20000"),
+        &input,
+        &encoded,
+    ]);
+    raptorq(&[Path::new("decode"), &encoded, &output]);
+    assert_eq!(fs::read(&output).unwrap(), data);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn directory_round_trip() {
+    let dir = temp_dir("directory");
+    let (input, data) = write_input(&dir);
+    let encoded = dir.join("encoded");
+    let output = dir.join("output");
+
+    raptorq(&[
+        Path::new("encode"),
+        Path::new("--repair"),
+        Path::new("20"),
+        &input,
+        &encoded,
+    ]);
+    // Losing a few source packets is recovered from the repair packets
+    for esi in 0..10 {
+        fs::remove_file(encoded.join(format!("0_{esi}.packet"))).unwrap();
+    }
+    raptorq(&[Path::new("decode"), &encoded, &output]);
+    assert_eq!(fs::read(&output).unwrap(), data);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn reject_empty_input() {
+    let dir = temp_dir("empty");
+    let input = dir.join("input");
+    fs::write(&input, []).unwrap();
+
+    let message = raptorq_error(&[Path::new("encode"), &input, &dir.join("encoded")]);
+    assert!(message.contains("empty"), "{message}");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn reject_small_memory_limit() {
+    let dir = temp_dir("memory-limit");
+    let (input, _) = write_input(&dir);
+
+    for limit in ["0", "13999"] {
+        let message = raptorq_error(&[
+            Path::new("encode"),
+            Path::new("--memory-limit"),
+            Path::new(limit),
+            &input,
+            &dir.join("encoded"),
+        ]);
+        assert!(message.contains("--memory-limit"), "{message}");
+    }
+    assert!(!dir.join("encoded").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}